@@ -0,0 +1,52 @@
+//! hardware task bound to a synchronous exception
+#![no_main]
+#![no_std]
+#![deny(warnings)]
+#![deny(unsafe_code)]
+#![deny(missing_docs)]
+
+use hifive1 as _;
+use riscv_rt as _;
+
+#[rtic::app(device = e310x, backend = { hart_id = HART0 })]
+mod app {
+    use riscv::register::mepc;
+    use semihosting::{println, process::exit};
+
+    #[shared]
+    struct Shared {}
+
+    #[local]
+    struct Local {}
+
+    #[init]
+    fn init(_: init::Context) -> (Shared, Local) {
+        foo::spawn().unwrap();
+
+        (Shared {}, Local {})
+    }
+
+    #[task(priority = 2)]
+    async fn foo(_: foo::Context) {
+        println!("foo: before ecall");
+
+        // Traps into `env_call`, which preempts every priority level
+        #[allow(unsafe_code)]
+        unsafe {
+            core::arch::asm!("ecall")
+        };
+
+        println!("foo: after ecall");
+
+        exit(0); // Exit QEMU simulator
+    }
+
+    // Runs above the highest priority of the app, at priority 3
+    #[task(binds = MachineEnvCall)]
+    fn env_call(_: env_call::Context) {
+        println!("env_call at priority {}", rtic::current_priority());
+
+        // Resume after the `ecall` instruction
+        mepc::write(mepc::read() + 4);
+    }
+}
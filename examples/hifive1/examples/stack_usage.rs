@@ -0,0 +1,55 @@
+//! stack usage of each priority level
+#![no_main]
+#![no_std]
+#![deny(warnings)]
+#![deny(unsafe_code)]
+#![deny(missing_docs)]
+
+use hifive1 as _;
+use riscv_rt as _;
+
+#[rtic::app(
+    device = e310x,
+    backend = { hart_id = HART0, stack_paint = true, stack_guard = 1024 }
+)]
+mod app {
+    use semihosting::{println, process::exit};
+
+    #[shared]
+    struct Shared {}
+
+    #[local]
+    struct Local {}
+
+    #[init]
+    fn init(_: init::Context) -> (Shared, Local) {
+        foo::spawn().unwrap();
+
+        (Shared {}, Local {})
+    }
+
+    #[task(priority = 1)]
+    async fn foo(_: foo::Context) {
+        println!("foo");
+
+        // `baz` preempts `foo`, its stack usage is recorded when its dispatcher returns
+        baz::spawn().unwrap();
+
+        println!(
+            "baz used the stack: {}",
+            rtic::stack::high_water_mark(2) > 0
+        );
+        println!(
+            "max usage within the stack: {}",
+            rtic::stack::max_usage() <= rtic::stack::size()
+        );
+
+        exit(0); // Exit QEMU simulator
+    }
+
+    #[task(priority = 2)]
+    async fn baz(_: baz::Context) {
+        let buffer = [0u8; 64];
+        println!("baz {}", buffer.len());
+    }
+}
@@ -0,0 +1,63 @@
+//! examples/async-hardware-task.rs
+
+#![no_main]
+#![no_std]
+#![deny(warnings)]
+#![deny(unsafe_code)]
+#![deny(missing_docs)]
+
+use panic_semihosting as _;
+
+// No dispatchers are needed, the async task runs in its own interrupt handler
+#[rtic::app(device = lm3s6965)]
+mod app {
+    use cortex_m_semihosting::{debug, hprintln};
+    use lm3s6965::Interrupt;
+    use rtic_sync::{channel::*, make_channel};
+
+    const CAPACITY: usize = 1;
+
+    #[shared]
+    struct Shared {}
+
+    #[local]
+    struct Local {
+        sender: Sender<'static, u32, CAPACITY>,
+        receiver: Receiver<'static, u32, CAPACITY>,
+    }
+
+    #[init]
+    fn init(_: init::Context) -> (Shared, Local) {
+        let (sender, receiver) = make_channel!(u32, CAPACITY);
+
+        // The first interrupt starts the task
+        rtic::pend(Interrupt::UART0);
+
+        (Shared {}, Local { sender, receiver })
+    }
+
+    #[idle(local = [sender])]
+    fn idle(cx: idle::Context) -> ! {
+        for val in 1..=2 {
+            // Waking the receiver pends `UART0`, which polls `uart0`
+            cx.local.sender.try_send(val).unwrap();
+        }
+
+        loop {
+            cortex_m::asm::nop();
+        }
+    }
+
+    #[task(binds = UART0, priority = 2, local = [receiver])]
+    async fn uart0(cx: uart0::Context) {
+        hprintln!("uart0: started");
+
+        while let Ok(val) = cx.local.receiver.recv().await {
+            hprintln!("uart0: received {}", val);
+
+            if val == 2 {
+                debug::exit(debug::EXIT_SUCCESS); // Exit QEMU simulator
+            }
+        }
+    }
+}
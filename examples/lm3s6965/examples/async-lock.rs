@@ -0,0 +1,67 @@
+//! examples/async-lock.rs
+
+#![no_main]
+#![no_std]
+#![deny(warnings)]
+#![deny(unsafe_code)]
+#![deny(missing_docs)]
+
+use panic_semihosting as _;
+
+#[rtic::app(device = lm3s6965, dispatchers = [SSI0, UART0], peripherals = true)]
+mod app {
+    use cortex_m_semihosting::{debug, hprintln};
+    use rtic_monotonics::systick::prelude::*;
+
+    systick_monotonic!(Mono, 100);
+
+    #[shared]
+    struct Shared {
+        // A bus that is held across `.await` points during a transaction
+        #[async_lock]
+        bus: u32,
+    }
+
+    #[local]
+    struct Local {}
+
+    #[init]
+    fn init(cx: init::Context) -> (Shared, Local) {
+        hprintln!("init");
+
+        Mono::start(cx.core.SYST, 12_000_000);
+
+        foo::spawn().ok();
+        bar::spawn().ok();
+
+        (Shared { bus: 0 }, Local {})
+    }
+
+    #[task(shared = [bus])]
+    async fn foo(mut cx: foo::Context) {
+        let mut bus = cx.shared.bus.async_lock().await;
+        hprintln!("foo: start transaction");
+        *bus += 1;
+        Mono::delay(100.millis()).await;
+        hprintln!("foo: end transaction, bus = {}", *bus);
+    }
+
+    #[task(priority = 2, shared = [bus])]
+    async fn bar(mut cx: bar::Context) {
+        // Let `foo` start its transaction first
+        Mono::delay(10.millis()).await;
+
+        hprintln!("bar: waiting for the bus");
+        let mut bus = cx.shared.bus.async_lock().await;
+        hprintln!("bar: start transaction");
+        *bus += 1;
+        Mono::delay(100.millis()).await;
+        hprintln!("bar: end transaction, bus = {}", *bus);
+        drop(bus);
+
+        // The ordinary `lock` can be used when the resource is not held by `async_lock`
+        cx.shared.bus.lock(|bus| hprintln!("bar: bus = {}", *bus));
+
+        debug::exit(debug::EXIT_SUCCESS); // Exit QEMU simulator
+    }
+}
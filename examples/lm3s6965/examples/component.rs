@@ -0,0 +1,84 @@
+//! examples/component.rs
+
+#![no_main]
+#![no_std]
+#![deny(warnings)]
+#![deny(unsafe_code)]
+#![deny(missing_docs)]
+
+use panic_semihosting as _;
+
+/// Stand-in for a driver crate
+pub mod driver {
+    /// Handles the driver's interrupt
+    pub fn on_interrupt(count: &mut u32) -> u32 {
+        *count += 1;
+        *count
+    }
+}
+
+// A driver crate exports this macro with `#[macro_export]`
+macro_rules! driver_component {
+    (
+        $callback:path, { $($state:tt)* },
+        name = $name:ident, irq = $irq:ident, priority = $prio:literal $(,)?
+    ) => {
+        $callback! {
+            { $($state)* }
+
+            /// Driver tasks and resources
+            mod $name {
+                // The driver must preempt the tasks that read its state
+                const _: () = assert!($prio >= 2, "the driver requires at least priority 2");
+
+                #[shared]
+                struct Shared {
+                    irq_count: u32,
+                }
+
+                #[task(binds = $irq, priority = $prio, shared = [irq_count])]
+                fn driver_irq(mut cx: driver_irq::Context) {
+                    let n = cx.shared.irq_count.lock($crate::driver::on_interrupt);
+
+                    ::cortex_m_semihosting::hprintln!("driver: interrupt {}", n);
+                }
+            }
+        }
+    };
+}
+
+#[rtic::app(device = lm3s6965)]
+mod app {
+    use cortex_m_semihosting::{debug, hprintln};
+    use lm3s6965::Interrupt;
+
+    #[shared]
+    struct Shared {}
+
+    #[local]
+    struct Local {}
+
+    #[init]
+    fn init(_: init::Context) -> (Shared, Local) {
+        rtic::pend(Interrupt::UART0);
+
+        // `irq_count` is declared by the component
+        (Shared { irq_count: 0 }, Local {})
+    }
+
+    #[idle(shared = [irq_count])]
+    fn idle(mut cx: idle::Context) -> ! {
+        let n = cx.shared.irq_count.lock(|irq_count| *irq_count);
+
+        hprintln!("idle: irq_count = {}", n);
+
+        debug::exit(debug::EXIT_SUCCESS); // Exit QEMU simulator
+
+        loop {
+            cortex_m::asm::nop();
+        }
+    }
+
+    #[component]
+    driver_component!(name = uart, irq = UART0, priority = 2);
+}
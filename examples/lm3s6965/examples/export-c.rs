@@ -0,0 +1,67 @@
+//! examples/export-c.rs
+
+#![no_main]
+#![no_std]
+#![deny(warnings)]
+#![deny(unsafe_code)]
+#![deny(missing_docs)]
+
+use panic_semihosting as _;
+
+/// Stand-in for a C library that delivers events through C callbacks
+#[allow(unsafe_code)]
+pub mod c_sdk {
+    extern "C" {
+        // The C library calls the entry points generated by `export_c` by name
+        fn ble_event(kind: u8, len: u16) -> bool;
+        fn rx_done_spawn() -> bool;
+    }
+
+    /// The C library delivers an event
+    pub fn deliver_event(kind: u8, len: u16) -> bool {
+        unsafe { ble_event(kind, len) }
+    }
+
+    /// A DMA transfer of the C library completes
+    pub fn complete_rx() -> bool {
+        unsafe { rx_done_spawn() }
+    }
+}
+
+#[rtic::app(device = lm3s6965, dispatchers = [SSI0, QEI0])]
+mod app {
+    use super::c_sdk;
+    use cortex_m_semihosting::{debug, hprintln};
+
+    #[shared]
+    struct Shared {}
+
+    #[local]
+    struct Local {}
+
+    #[init]
+    fn init(_: init::Context) -> (Shared, Local) {
+        hprintln!("init: event spawned = {}", c_sdk::deliver_event(1, 27));
+
+        // `on_event` is already spawned and has not run yet
+        hprintln!("init: event spawned = {}", c_sdk::deliver_event(2, 0));
+
+        (Shared {}, Local {})
+    }
+
+    // Called from C as `bool ble_event(uint8_t kind, uint16_t len)`
+    #[task(priority = 1, export_c = "ble_event")]
+    async fn on_event(_: on_event::Context, kind: u8, len: u16) {
+        hprintln!("on_event: kind = {}, len = {}", kind, len);
+
+        c_sdk::complete_rx();
+    }
+
+    // Called from C as `bool rx_done_spawn(void)`
+    #[task(priority = 2, export_c)]
+    async fn rx_done(_: rx_done::Context) {
+        hprintln!("rx_done");
+
+        debug::exit(debug::EXIT_SUCCESS); // Exit QEMU simulator
+    }
+}
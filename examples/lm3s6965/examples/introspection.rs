@@ -0,0 +1,57 @@
+//! examples/introspection.rs
+
+#![no_main]
+#![no_std]
+#![deny(warnings)]
+#![deny(unsafe_code)]
+#![deny(missing_docs)]
+
+use panic_semihosting as _;
+
+#[rtic::app(device = lm3s6965, dispatchers = [SSI0, QEI0], peripherals = true, monotonic = Mono)]
+mod app {
+    use cortex_m_semihosting::{debug, hprintln};
+    use rtic_monotonics::systick::prelude::*;
+
+    systick_monotonic!(Mono, 100);
+
+    #[shared]
+    struct Shared {}
+
+    #[local]
+    struct Local {}
+
+    #[init]
+    fn init(cx: init::Context) -> (Shared, Local) {
+        hprintln!("{} at priority {}", cx.name(), cx.priority());
+
+        Mono::start(cx.core.SYST, 12_000_000);
+
+        foo::spawn().ok();
+
+        (Shared {}, Local {})
+    }
+
+    #[task(priority = 1)]
+    async fn foo(cx: foo::Context) {
+        log(cx.name());
+
+        Mono::delay(20.millis()).await;
+        let elapsed = Mono::now() - cx.spawn_instant();
+        hprintln!("foo: 20 ms since spawn: {}", elapsed.to_millis() >= 20);
+
+        bar::spawn().ok();
+    }
+
+    #[task(priority = 2)]
+    async fn bar(cx: bar::Context) {
+        log(cx.name());
+
+        debug::exit(debug::EXIT_SUCCESS); // Exit QEMU simulator
+    }
+
+    // E.g. a logger that tags messages with the priority they are logged from
+    fn log(task: &str) {
+        hprintln!("[priority {}] {}", rtic::current_priority(), task);
+    }
+}
@@ -0,0 +1,61 @@
+//! examples/isolation.rs
+
+#![no_main]
+#![no_std]
+#![deny(warnings)]
+#![deny(unsafe_code)]
+#![deny(missing_docs)]
+
+use panic_semihosting as _;
+
+// The resources can not be accessed from the priority levels that do not use them
+#[rtic::app(device = lm3s6965, dispatchers = [SSI0, QEI0], backend = { isolation = true })]
+mod app {
+    use cortex_m_semihosting::{debug, hprintln};
+
+    #[shared]
+    struct Shared {
+        // Used by both levels, so it can not be accessed from `idle`
+        total: u32,
+    }
+
+    #[local]
+    struct Local {
+        low: u32,
+        high: u32,
+    }
+
+    #[init]
+    fn init(_: init::Context) -> (Shared, Local) {
+        hprintln!("init");
+
+        foo::spawn().unwrap();
+
+        (Shared { total: 0 }, Local { low: 0, high: 0 })
+    }
+
+    // `low` can not be accessed while priority level 2 runs
+    #[task(priority = 1, local = [low], shared = [total])]
+    async fn foo(mut cx: foo::Context) {
+        *cx.local.low += 1;
+        hprintln!("foo: low = {}", cx.local.low);
+
+        bar::spawn().unwrap();
+
+        cx.shared.total.lock(|total| {
+            *total += 1;
+            hprintln!("foo: total = {}", *total);
+        });
+
+        debug::exit(debug::EXIT_SUCCESS); // Exit QEMU simulator
+    }
+
+    // `high` can not be accessed while priority level 1 runs
+    #[task(priority = 2, local = [high], shared = [total])]
+    async fn bar(mut cx: bar::Context) {
+        *cx.local.high += 1;
+        hprintln!("bar: high = {}", cx.local.high);
+
+        cx.shared.total.lock(|total| *total += 1);
+    }
+}
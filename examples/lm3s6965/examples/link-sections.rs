@@ -0,0 +1,53 @@
+//! examples/link-sections.rs
+
+#![no_main]
+#![no_std]
+#![deny(warnings)]
+#![deny(unsafe_code)]
+#![deny(missing_docs)]
+
+use panic_semihosting as _;
+
+// The executors of the tasks without their own `section` are placed in `.uninit.executors`
+#[rtic::app(device = lm3s6965, dispatchers = [SSI0], executor_section = ".uninit.executors")]
+mod app {
+    use cortex_m_semihosting::{debug, hprintln};
+
+    #[shared]
+    struct Shared {
+        // On a real target e.g. non-cached RAM for DMA, `cortex-m-rt` keeps `.uninit.*` sections
+        // in RAM without initializing them
+        #[section(".uninit.dma")]
+        buffer: [u8; 16],
+    }
+
+    #[local]
+    struct Local {}
+
+    #[init]
+    fn init(_: init::Context) -> (Shared, Local) {
+        hprintln!("init");
+
+        foo::spawn().unwrap();
+
+        (Shared { buffer: [0xaa; 16] }, Local {})
+    }
+
+    // The state of the task is placed in the given section instead of on the stack of `main`,
+    // on a real target e.g. tightly coupled memory
+    #[task(priority = 1, shared = [buffer], section = ".uninit.tcm")]
+    async fn foo(mut cx: foo::Context) {
+        let first = cx.shared.buffer.lock(|buffer| buffer[0]);
+
+        hprintln!("foo: buffer[0] = {:#x}", first);
+
+        bar::spawn().unwrap();
+    }
+
+    #[task(priority = 1)]
+    async fn bar(_: bar::Context) {
+        hprintln!("bar");
+
+        debug::exit(debug::EXIT_SUCCESS); // Exit QEMU simulator
+    }
+}
@@ -0,0 +1,77 @@
+//! examples/lints.rs
+
+#![no_main]
+#![no_std]
+#![deny(missing_docs)]
+#![deny(unsafe_code)]
+
+use panic_semihosting as _;
+
+// `lints = true` warns about the resources and dispatchers below
+#[rtic::app(device = lm3s6965, dispatchers = [SSI0, UART0], lints = true)]
+mod app {
+    use cortex_m_semihosting::{debug, hprintln};
+
+    #[shared]
+    struct Shared {
+        // Only accessed by `foo`, can be a `#[local]` resource
+        count: u32,
+
+        // Only accessed at priority 2 by tasks that are not async, can be `#[lock_free]`
+        total: u32,
+
+        // Never accessed
+        unused: u32,
+    }
+
+    #[local]
+    struct Local {
+        // Initialized with a constant, can be a task-local resource of `foo`
+        step: u32,
+
+        // Never used
+        spare: u8,
+    }
+
+    #[init]
+    fn init(_: init::Context) -> (Shared, Local) {
+        foo::spawn().unwrap();
+        bar::spawn().unwrap();
+
+        (
+            Shared {
+                count: 0,
+                total: 0,
+                unused: 0,
+            },
+            Local { step: 1, spare: 0 },
+        )
+    }
+
+    // `SSI0` is never used, all software tasks run at priority 1
+    #[task(priority = 1, shared = [count], local = [step])]
+    async fn foo(mut cx: foo::Context) {
+        let step = *cx.local.step;
+        cx.shared.count.lock(|count| *count += step);
+
+        rtic::pend(lm3s6965::Interrupt::UART1);
+    }
+
+    #[task(priority = 1)]
+    async fn bar(_: bar::Context) {
+        debug::exit(debug::EXIT_SUCCESS); // Exit QEMU simulator
+    }
+
+    #[task(binds = UART1, priority = 2, shared = [total])]
+    fn uart1(mut cx: uart1::Context) {
+        cx.shared.total.lock(|total| *total += 1);
+
+        rtic::pend(lm3s6965::Interrupt::GPIOA);
+    }
+
+    #[task(binds = GPIOA, priority = 2, shared = [total])]
+    fn gpioa(mut cx: gpioa::Context) {
+        let total = cx.shared.total.lock(|total| *total);
+        hprintln!("gpioa: total = {}", total);
+    }
+}
@@ -0,0 +1,77 @@
+//! examples/registry.rs
+
+#![no_main]
+#![no_std]
+#![deny(warnings)]
+#![deny(unsafe_code)]
+#![deny(missing_docs)]
+
+use panic_semihosting as _;
+
+#[rtic::app(device = lm3s6965, dispatchers = [SSI0, QEI0], registry = true)]
+mod app {
+    use cortex_m_semihosting::{debug, hprintln};
+
+    #[shared]
+    struct Shared {}
+
+    #[local]
+    struct Local {}
+
+    #[init]
+    fn init(_: init::Context) -> (Shared, Local) {
+        // A debug console lists the tasks ...
+        for task in registry::TASKS.iter() {
+            hprintln!(
+                "{}: {} (priority {}, running: {:?})",
+                task.id,
+                task.name,
+                task.priority,
+                registry::is_running(task.id)
+            );
+        }
+
+        // ... and triggers them by name, with their arguments encoded as little endian
+        let set_level = registry::find("set_level").unwrap();
+        hprintln!(
+            "spawn set_level: {:?}",
+            registry::spawn_by_id(set_level.id, &[3, 0x10, 0x00, 1])
+        );
+        hprintln!(
+            "spawn set_level: {:?}",
+            registry::spawn_by_id(set_level.id, &[4, 0x20, 0x00, 0])
+        );
+        hprintln!(
+            "spawn set_level: {:?}",
+            registry::spawn_by_id(set_level.id, &[3])
+        );
+
+        let report = registry::find("report").unwrap();
+        hprintln!("spawn report: {:?}", registry::spawn_by_id(report.id, &[]));
+        hprintln!("report running: {:?}", registry::is_running(report.id));
+
+        let internal = registry::find("internal").unwrap();
+        hprintln!(
+            "spawn internal: {:?}",
+            registry::spawn_by_id(internal.id, &[])
+        );
+
+        (Shared {}, Local {})
+    }
+
+    #[task(priority = 2, spawn_by_id)]
+    async fn set_level(_: set_level::Context, channel: u8, level: u16, enable: bool) {
+        hprintln!("set_level: {} {} {}", channel, level, enable);
+    }
+
+    #[task(priority = 1, spawn_by_id)]
+    async fn report(_: report::Context) {
+        hprintln!("report");
+
+        debug::exit(debug::EXIT_SUCCESS); // Exit QEMU simulator
+    }
+
+    // Not spawnable from the console
+    #[task(priority = 1)]
+    async fn internal(_: internal::Context) {}
+}
@@ -0,0 +1,60 @@
+//! examples/shared-readers.rs
+
+#![no_main]
+#![no_std]
+#![deny(warnings)]
+#![deny(unsafe_code)]
+#![deny(missing_docs)]
+
+use panic_semihosting as _;
+
+#[rtic::app(device = lm3s6965, dispatchers = [SSI0])]
+mod app {
+    use cortex_m_semihosting::{debug, hprintln};
+    use lm3s6965::Interrupt;
+
+    #[shared]
+    struct Shared {
+        gain: u32,
+    }
+
+    #[local]
+    struct Local {}
+
+    #[init]
+    fn init(_: init::Context) -> (Shared, Local) {
+        rtic::pend(Interrupt::UART0);
+
+        (Shared { gain: 1 }, Local {})
+    }
+
+    // The only writer of `gain`
+    #[task(binds = UART0, priority = 1, shared = [gain])]
+    fn update(mut cx: update::Context) {
+        cx.shared.gain.lock(|gain| {
+            *gain = 2;
+
+            // `control` reads `gain`, so it does not preempt the lock
+            rtic::pend(Interrupt::UART1);
+
+            hprintln!("update: gain = {}", gain);
+        });
+
+        log::spawn().unwrap();
+    }
+
+    // No writer can preempt `control`, so it gets a plain `&u32`
+    #[task(binds = UART1, priority = 2, shared = [&gain])]
+    fn control(cx: control::Context) {
+        let gain: &u32 = cx.shared.gain;
+        hprintln!("control: gain = {}", gain);
+    }
+
+    // An async reader copies `gain` out, as a writer may run while it awaits
+    #[task(priority = 1, shared = [&gain])]
+    async fn log(cx: log::Context) {
+        hprintln!("log: gain = {}", cx.shared.gain.read());
+
+        debug::exit(debug::EXIT_SUCCESS); // Exit QEMU simulator
+    }
+}
@@ -0,0 +1,47 @@
+//! examples/stack-guard.rs
+
+#![no_main]
+#![no_std]
+#![deny(warnings)]
+#![deny(unsafe_code)]
+#![deny(missing_docs)]
+
+use panic_semihosting as _;
+
+// A stack overflow into the 256 byte region below the stack faults instead of corrupting `.bss`
+#[rtic::app(device = lm3s6965, dispatchers = [SSI0], backend = { stack_guard = 256 })]
+mod app {
+    use cortex_m_semihosting::{debug, hprintln};
+
+    #[shared]
+    struct Shared {}
+
+    #[local]
+    struct Local {}
+
+    #[init]
+    fn init(_: init::Context) -> (Shared, Local) {
+        hprintln!("init");
+
+        foo::spawn().unwrap();
+
+        (Shared {}, Local {})
+    }
+
+    #[task(priority = 1)]
+    async fn foo(_: foo::Context) {
+        hprintln!("foo: depth {}", depth(8));
+
+        debug::exit(debug::EXIT_SUCCESS); // Exit QEMU simulator
+    }
+
+    // Uses some stack, well above the guard
+    #[inline(never)]
+    fn depth(n: u32) -> u32 {
+        if n == 0 {
+            0
+        } else {
+            1 + depth(n - 1)
+        }
+    }
+}
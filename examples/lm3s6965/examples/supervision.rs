@@ -0,0 +1,67 @@
+//! examples/supervision.rs
+
+#![no_main]
+#![no_std]
+#![deny(warnings)]
+#![deny(unsafe_code)]
+#![deny(missing_docs)]
+
+use panic_semihosting as _;
+
+#[rtic::app(device = lm3s6965, dispatchers = [SSI0])]
+mod app {
+    use core::sync::atomic::{AtomicU8, Ordering};
+    use cortex_m_semihosting::{debug, hprintln};
+    use rtic::supervisor::TaskFailure;
+
+    /// Errors of the protocol task
+    #[derive(Debug)]
+    pub enum ProtocolError {
+        /// The peer did not respond
+        Timeout,
+    }
+
+    static SESSION: AtomicU8 = AtomicU8::new(0);
+
+    #[shared]
+    struct Shared {}
+
+    #[local]
+    struct Local {}
+
+    #[init]
+    fn init(_: init::Context) -> (Shared, Local) {
+        hprintln!("init");
+
+        protocol::spawn(next_session()).ok();
+
+        (Shared {}, Local {})
+    }
+
+    // Creates the arguments of `protocol` when it is (re)started
+    fn next_session() -> u8 {
+        let session = SESSION.load(Ordering::Relaxed);
+        SESSION.store(session + 1, Ordering::Relaxed);
+        session
+    }
+
+    #[task(priority = 1, restart = on_error, max_restarts = 2, restart_args = next_session)]
+    async fn protocol(_: protocol::Context, session: u8) -> Result<(), ProtocolError> {
+        hprintln!("protocol: session {}", session);
+
+        Err(ProtocolError::Timeout)
+    }
+
+    // Called when a supervised task fails and is not restarted
+    #[task_failed]
+    fn task_failed(failure: TaskFailure) {
+        hprintln!(
+            "task_failed: {} failed after {} restarts: {:?}",
+            failure.task,
+            failure.restarts,
+            failure.error
+        );
+
+        debug::exit(debug::EXIT_SUCCESS); // Exit QEMU simulator
+    }
+}
@@ -0,0 +1,83 @@
+//! examples/task-modules.rs
+
+#![no_main]
+#![no_std]
+#![deny(warnings)]
+#![deny(unsafe_code)]
+#![deny(missing_docs)]
+
+use panic_semihosting as _;
+
+#[rtic::app(device = lm3s6965, dispatchers = [SSI0, QEI0])]
+mod app {
+    use lm3s6965::Interrupt;
+
+    #[shared]
+    struct Shared {
+        counter: u32,
+    }
+
+    #[local]
+    struct Local {}
+
+    #[init]
+    fn init(_: init::Context) -> (Shared, Local) {
+        rtic::pend(Interrupt::UART0);
+
+        (
+            Shared {
+                counter: 0,
+                // Declared in `mod rx`
+                rx_count: 0,
+            },
+            Local {
+                // Declared in `mod report`
+                prefix: "report",
+            },
+        )
+    }
+
+    /// Tasks and resources owned by the receive path
+    mod rx {
+        use cortex_m_semihosting::hprintln;
+
+        #[shared]
+        struct Shared {
+            rx_count: u32,
+        }
+
+        #[task(binds = UART0, shared = [counter, rx_count])]
+        fn uart0(mut cx: uart0::Context) {
+            let n = cx.shared.rx_count.lock(|rx_count| bump(rx_count));
+            cx.shared.counter.lock(|counter| *counter += 1);
+
+            hprintln!("uart0: rx_count = {}", n);
+
+            super::print::spawn().unwrap();
+        }
+
+        fn bump(rx_count: &mut u32) -> u32 {
+            *rx_count += 1;
+            *rx_count
+        }
+    }
+
+    /// Reporting of what the receive path has seen
+    mod report {
+        use cortex_m_semihosting::{debug, hprintln};
+
+        #[local]
+        struct Local {
+            prefix: &'static str,
+        }
+
+        #[task(priority = 2, shared = [counter], local = [prefix])]
+        async fn print(mut cx: print::Context) {
+            let counter = cx.shared.counter.lock(|counter| *counter);
+
+            hprintln!("{}: counter = {}", cx.local.prefix, counter);
+
+            debug::exit(debug::EXIT_SUCCESS); // Exit QEMU simulator
+        }
+    }
+}
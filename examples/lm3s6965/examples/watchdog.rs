@@ -0,0 +1,66 @@
+//! examples/watchdog.rs
+
+#![no_main]
+#![no_std]
+#![deny(warnings)]
+#![deny(unsafe_code)]
+#![deny(missing_docs)]
+
+use panic_semihosting as _;
+
+#[rtic::app(device = lm3s6965, dispatchers = [SSI0, UART0, UART1], peripherals = true, monotonic = Mono)]
+mod app {
+    use cortex_m_semihosting::{debug, hprintln};
+    use rtic_monotonics::systick::prelude::*;
+
+    systick_monotonic!(Mono, 100);
+
+    #[shared]
+    struct Shared {}
+
+    #[local]
+    struct Local {}
+
+    #[init]
+    fn init(cx: init::Context) -> (Shared, Local) {
+        hprintln!("init");
+
+        Mono::start(cx.core.SYST, 12_000_000);
+
+        monitor::spawn().ok();
+        worker::spawn().ok();
+        stuck::spawn().ok();
+
+        (Shared {}, Local {})
+    }
+
+    // Feeds the hardware watchdog while all tasks check in on time, above the tasks it supervises
+    #[task(priority = 3)]
+    async fn monitor(cx: monitor::Context) {
+        watchdog::monitor(&cx, 100.millis(), || hprintln!("feed")).await;
+    }
+
+    #[task(priority = 1, watchdog = 250.millis())]
+    async fn worker(cx: worker::Context) {
+        loop {
+            cx.check_in();
+            Mono::delay(100.millis()).await;
+        }
+    }
+
+    // Checks in once, then takes too long
+    #[task(priority = 2, watchdog = 250.millis())]
+    async fn stuck(cx: stuck::Context) {
+        hprintln!("stuck: check in");
+        cx.check_in();
+        Mono::delay(1.secs()).await;
+    }
+
+    // Called with the name of the stuck task, the hardware watchdog is no longer fed
+    #[watchdog_expired]
+    fn watchdog_expired(task: &'static str) {
+        hprintln!("watchdog expired: {}", task);
+
+        debug::exit(debug::EXIT_SUCCESS); // Exit QEMU simulator
+    }
+}
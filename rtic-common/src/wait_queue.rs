@@ -104,6 +104,52 @@ impl<T: Clone> DoublyLinkedList<T> {
         });
     }
 
+    /// Put an element in the queue, before the first element for which `before` returns true.
+    /// If there is none, the element is put at the back of the queue.
+    ///
+    /// The queue is searched from the front in a single critical section.
+    ///
+    /// # Safety
+    ///
+    /// The link must live until it is removed from the queue.
+    pub unsafe fn push_before(&self, link: Pin<&Link<T>>, before: impl Fn(&T) -> bool) {
+        cs::with(|_| {
+            // Make sure all previous writes are visible
+            core::sync::atomic::fence(Ordering::SeqCst);
+
+            // SAFETY: This datastructure does not move the underlying value.
+            let link = link.get_ref();
+            let link_ptr = link as *const _ as *mut _;
+
+            let mut next = self.head.load(Self::R);
+
+            // SAFETY: `as_ref` is safe as `insert` requires a valid reference to a link
+            while let Some(next_ref) = unsafe { next.as_ref() } {
+                if before(&next_ref.val) {
+                    // Insert between `prev` and `next`
+                    let prev = next_ref.prev.load(Self::R);
+
+                    link.prev.store(prev, Self::R);
+                    link.next.store(next, Self::R);
+                    next_ref.prev.store(link_ptr, Self::R);
+
+                    if let Some(prev_ref) = unsafe { prev.as_ref() } {
+                        prev_ref.next.store(link_ptr, Self::R);
+                    } else {
+                        self.head.store(link_ptr, Self::R);
+                    }
+
+                    return;
+                }
+
+                next = next_ref.next.load(Self::R);
+            }
+
+            // No element to put it before, put it at the back.
+            unsafe { self.push(Pin::new_unchecked(link)) };
+        });
+    }
+
     /// Check if the queue is empty.
     pub fn is_empty(&self) -> bool {
         self.head.load(Self::R).is_null()
@@ -281,4 +327,31 @@ mod tests {
         i5.remove_from_list(&wq);
         wq.print();
     }
+
+    #[test]
+    fn push_before() {
+        let wq = DoublyLinkedList::<u32>::new();
+
+        let i1 = Link::new(10);
+        let i2 = Link::new(30);
+        let i3 = Link::new(20);
+        let i4 = Link::new(30);
+        let i5 = Link::new(40);
+
+        // Keep the queue sorted from high to low, in FIFO order for equal values
+        for link in [&i1, &i2, &i3, &i4, &i5] {
+            unsafe { wq.push_before(Pin::new_unchecked(link), |v| *v < link.val) };
+        }
+
+        wq.print();
+
+        assert_eq!(wq.pop(), Some(40));
+        assert_eq!(wq.pop(), Some(30));
+        assert!(i2.is_popped());
+        assert_eq!(wq.pop(), Some(30));
+        assert_eq!(wq.pop(), Some(20));
+
+        i1.remove_from_list(&wq);
+        assert!(wq.is_empty());
+    }
 }
@@ -53,6 +53,15 @@ impl CriticalSectionWakerRegistration {
         });
     }
 
+    /// Drop the registered waker without waking it.
+    pub fn clear(&self) {
+        critical_section::with(|_| {
+            // SAFETY: This access is protected by the critical section.
+            let self_waker = unsafe { &mut *self.waker.get() };
+            *self_waker = None;
+        });
+    }
+
     /// Wake the waker.
     pub fn wake(&self) {
         critical_section::with(|_| {
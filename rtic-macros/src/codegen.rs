@@ -17,10 +17,14 @@ mod local_resources_struct;
 mod module;
 mod post_init;
 mod pre_init;
+mod registry;
 mod shared_resources;
 mod shared_resources_struct;
 mod software_tasks;
+mod task_modules;
 mod util;
+mod warnings;
+mod watchdog;
 
 mod main;
 
@@ -39,10 +43,15 @@ pub fn app(app: &App, analysis: &Analysis) -> TokenStream2 {
     let hardware_tasks_codegen = hardware_tasks::codegen(app, analysis);
     let software_tasks_codegen = software_tasks::codegen(app, analysis);
     let async_dispatchers_codegen = async_dispatchers::codegen(app, analysis);
+    let task_modules_codegen = task_modules::codegen(app);
+    let registry_codegen = registry::codegen(app);
+    let watchdog_codegen = watchdog::codegen(app);
+    let warnings_codegen = warnings::codegen(app, analysis);
 
     let user_imports = &app.user_imports;
     let user_code = &app.user_code;
     let name = &app.name;
+    let attrs = &app.attrs;
     let device = &app.args.device;
 
     let rt_err = util::rt_err_ident();
@@ -50,6 +59,7 @@ pub fn app(app: &App, analysis: &Analysis) -> TokenStream2 {
 
     quote!(
         /// The RTIC application module
+        #(#attrs)*
         pub mod #name {
             /// Always include the device crate which contains the vector table
             use #device as #rt_err;
@@ -69,6 +79,12 @@ pub fn app(app: &App, analysis: &Analysis) -> TokenStream2 {
 
             #software_tasks_codegen
 
+            #task_modules_codegen
+
+            #registry_codegen
+
+            #watchdog_codegen
+
             #shared_resources_codegen
 
             #local_resources_codegen
@@ -76,6 +92,8 @@ pub fn app(app: &App, analysis: &Analysis) -> TokenStream2 {
             #async_dispatchers_codegen
 
             #main
+
+            #warnings_codegen
         }
     )
 }
@@ -1,11 +1,13 @@
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
+use syn::Ident;
 
 use super::bindings::extra_assertions;
 use crate::analyze::Analysis;
 use crate::syntax::ast::App;
 
-/// Generates compile-time assertions that check that types implement the `Send` / `Sync` traits
+/// Generates compile-time assertions that check that types implement the `Send` / `Sync` / `Copy`
+/// and `ExportC` traits
 pub fn codegen(app: &App, analysis: &Analysis) -> Vec<TokenStream2> {
     let mut stmts = vec![];
 
@@ -17,6 +19,27 @@ pub fn codegen(app: &App, analysis: &Analysis) -> Vec<TokenStream2> {
         stmts.push(quote!(rtic::export::assert_sync::<#ty>();));
     }
 
+    // Resources read through a sequence lock are copied out, the alias points the error at the
+    // `&` access of the reader
+    for (priority, name, is_async) in app.shared_resource_reads() {
+        if analysis.seqlock_read(name, priority, is_async) {
+            let ty = &app.shared_resources[name].ty;
+            let reader = Ident::new("Reader", name.span());
+            stmts.push(quote!({
+                type #reader = #ty;
+                rtic::export::assert_seqlock_read::<#reader>();
+            }));
+        }
+    }
+
+    // The arguments of tasks spawned from C must be plain data
+    for task in app.software_tasks.values().filter(|task| task.args.export_c) {
+        for input in &task.inputs {
+            let ty = &input.ty;
+            stmts.push(quote!(rtic::export::assert_export_c::<#ty>();));
+        }
+    }
+
     stmts.append(&mut extra_assertions(app, analysis));
 
     stmts
@@ -1,4 +1,4 @@
-use crate::syntax::ast::App;
+use crate::syntax::ast::{App, RestartPolicy};
 use crate::{
     analyze::Analysis,
     codegen::{
@@ -8,6 +8,7 @@ use crate::{
 };
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
+use syn::Ident;
 
 /// Generates task dispatchers
 pub fn codegen(app: &App, analysis: &Analysis) -> TokenStream2 {
@@ -16,7 +17,7 @@ pub fn codegen(app: &App, analysis: &Analysis) -> TokenStream2 {
     let interrupts = &analysis.interrupts;
 
     // Generate executor definition and priority in global scope
-    for (name, _) in app.software_tasks.iter() {
+    for (name, task) in app.software_tasks.iter() {
         let exec_name = util::internal_task_ident(name, "EXEC");
 
         items.push(quote!(
@@ -24,6 +25,27 @@ pub fn codegen(app: &App, analysis: &Analysis) -> TokenStream2 {
             static #exec_name: rtic::export::executor::AsyncTaskExecutorPtr =
                 rtic::export::executor::AsyncTaskExecutorPtr::new();
         ));
+
+        if let Some(section) = util::executor_section(app, &task.args.section) {
+            let task_fn = util::task_fn_path(name, task.module.as_ref());
+            items.push(util::executor_storage(
+                name,
+                section,
+                &task_fn,
+                task.inputs.len(),
+                &task.cfgs,
+            ));
+        }
+
+        if task.args.restart.is_some() {
+            let restarts_name = util::internal_task_ident(name, "RESTARTS");
+
+            // Only accessed by the dispatcher of the task
+            items.push(quote!(
+                #[allow(non_upper_case_globals)]
+                static #restarts_name: rtic::RacyCell<u32> = rtic::RacyCell::new(0);
+            ));
+        }
     }
 
     for (&level, channel) in &analysis.channels {
@@ -45,21 +67,48 @@ pub fn codegen(app: &App, analysis: &Analysis) -> TokenStream2 {
         };
 
         for name in channel.tasks.iter() {
+            let task = &app.software_tasks[name];
             let exec_name = util::internal_task_ident(name, "EXEC");
-            let from_ptr_n_args =
-                util::from_ptr_n_args_ident(app.software_tasks[name].inputs.len());
+            let from_ptr_n_args = util::from_ptr_n_args_ident(task.inputs.len());
+            let task_fn = util::task_fn_path(name, task.module.as_ref());
 
             // TODO: Fix cfg
-            // let task = &app.software_tasks[name];
             // let cfgs = &task.cfgs;
 
-            stmts.push(quote!(
-                let exec = rtic::export::executor::AsyncTaskExecutor::#from_ptr_n_args(#name, &#exec_name);
+            let poll = quote!(
                 exec.poll(|| {
-                    let exec = rtic::export::executor::AsyncTaskExecutor::#from_ptr_n_args(#name, &#exec_name);
+                    let exec = rtic::export::executor::AsyncTaskExecutor::#from_ptr_n_args(#task_fn, &#exec_name);
                     exec.set_pending();
                     #pend_interrupt
-                });
+                })
+            );
+
+            // A completed task no longer checks in with the watchdog
+            let unregister = task.args.watchdog.as_ref().map(|_| {
+                let watchdog_name = util::internal_task_ident(name, "WATCHDOG");
+                quote!(#watchdog_name.clear();)
+            });
+
+            let poll = if let Some(supervise) = supervise(app, name) {
+                quote!(
+                    if let Some(output) = #poll {
+                        #unregister
+                        #supervise
+                    }
+                )
+            } else if let Some(unregister) = unregister {
+                quote!(
+                    if #poll.is_some() {
+                        #unregister
+                    }
+                )
+            } else {
+                quote!(#poll;)
+            };
+
+            stmts.push(quote!(
+                let exec = rtic::export::executor::AsyncTaskExecutor::#from_ptr_n_args(#task_fn, &#exec_name);
+                #poll
             ));
         }
 
@@ -104,3 +153,78 @@ pub fn codegen(app: &App, analysis: &Analysis) -> TokenStream2 {
 
     quote!(#(#items)*)
 }
+
+/// Generates the supervision of a completed task (its output is bound to `output`): restarting it
+/// according to its restart policy or reporting its failure to the `#[task_failed]` hook
+fn supervise(app: &App, name: &Ident) -> Option<TokenStream2> {
+    let task = &app.software_tasks[name];
+    let name_s = name.to_string();
+    let report = app.task_failed.as_ref().map(|hook| {
+        quote!(
+            #hook(rtic::supervisor::TaskFailure {
+                task: #name_s,
+                restarts,
+                error,
+            });
+        )
+    });
+
+    let Some(policy) = task.args.restart else {
+        // Tasks without a restart policy are only supervised if they can fail
+        task.output.as_ref()?;
+        let report = report?;
+
+        return Some(quote!(
+            if let Some(error) = rtic::supervisor::TaskOutput::error(&output) {
+                let restarts = 0;
+                #report
+            }
+        ));
+    };
+
+    let restarts_name = util::internal_task_ident(name, "RESTARTS");
+    let internal_spawn_ident = util::internal_task_ident(name, "spawn");
+    let (_, input_tupled, input_untupled, _) = util::regroup_inputs(&task.inputs);
+    let restart_args = task
+        .args
+        .restart_args
+        .as_ref()
+        .map(|path| quote!(let #input_tupled = #path();));
+    let restart = match policy {
+        RestartPolicy::Always => quote!(true),
+        RestartPolicy::OnError => quote!(error.is_some()),
+    };
+    let below_max = match task.args.max_restarts {
+        Some(max) => quote!(*restarts < #max),
+        None => quote!(true),
+    };
+    let report = report.map(|report| {
+        quote!(
+            else if let Some(error) = error {
+                let restarts = *restarts;
+                #report
+            }
+        )
+    });
+
+    Some(quote!(
+        #[allow(unused_variables)]
+        let error = rtic::supervisor::TaskOutput::error(&output);
+        // SAFETY: Only accessed by this dispatcher
+        let restarts = unsafe { &mut *#restarts_name.get_mut() };
+
+        // Only consecutive failures count against `max_restarts`
+        if error.is_none() {
+            *restarts = 0;
+        }
+
+        if #restart && (error.is_none() || #below_max) {
+            if error.is_some() {
+                *restarts += 1;
+            }
+            #restart_args
+            // The task has completed, so this only fails if it has been spawned since
+            #internal_spawn_ident(#(#input_untupled,)*).ok();
+        } #report
+    ))
+}
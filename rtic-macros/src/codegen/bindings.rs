@@ -30,3 +30,6 @@ pub use riscv_slic::*;
 
 #[cfg(feature = "riscv-slic")]
 mod riscv_slic;
+
+#[cfg(any(feature = "riscv-esp32c3", feature = "riscv-slic"))]
+mod riscv_common;
@@ -1,12 +1,15 @@
 use crate::{
     analyze::Analysis as CodegenAnalysis,
     codegen::util,
-    syntax::{analyze::Analysis as SyntaxAnalysis, ast::App},
+    syntax::{
+        analyze::Analysis as SyntaxAnalysis,
+        ast::{App, TaskLocal},
+    },
 };
 use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::quote;
-use std::collections::HashSet;
-use syn::{parse, Attribute, Ident};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use syn::{parse, Attribute, Ident, Type};
 
 #[cfg(feature = "cortex-m-basepri")]
 pub use basepri::*;
@@ -30,6 +33,101 @@ fn is_exception(name: &Ident) -> bool {
     )
 }
 
+/// MPU regions available for the isolated resources, region 0 is the stack guard
+const MPU_ISOLATION_REGIONS: usize = 7;
+
+/// A live resource static that is isolated by the MPU
+struct Isolated<'a> {
+    name: Ident,
+    cfgs: &'a [Attribute],
+    ty: &'a Type,
+}
+
+/// The statics of the live `#[shared]` and `#[local]` resources, grouped by the set of priority
+/// levels that access them, when the storage of the levels is isolated; each group gets an MPU
+/// region, in order from region 1
+///
+/// Resources of `#[init]`, task local resources declared in `#[task(local = [..])]` and resources
+/// with a user specified `link_section` are not isolated.
+fn isolated_regions<'a>(
+    app: &'a App,
+    analysis: &SyntaxAnalysis,
+) -> Vec<(BTreeSet<u8>, Vec<Isolated<'a>>)> {
+    let mut regions: BTreeMap<BTreeSet<u8>, Vec<_>> = BTreeMap::new();
+
+    if !app.args.backend.as_ref().is_some_and(|b| b.isolation) {
+        return vec![];
+    }
+
+    let has_link_section = |attrs: &[Attribute]| {
+        attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("link_section"))
+    };
+
+    for (name, res) in &app.shared_resources {
+        if !analysis.shared_resources.contains(name) || has_link_section(&res.attrs) {
+            continue;
+        }
+
+        let levels = app
+            .shared_resource_accesses()
+            .filter(|(_, accessed, _)| *accessed == name)
+            .filter_map(|(priority, _, _)| priority)
+            .collect::<BTreeSet<_>>();
+
+        if !levels.is_empty() {
+            regions.entry(levels).or_default().push(Isolated {
+                name: util::static_shared_resource_ident(name),
+                cfgs: &res.cfgs,
+                ty: &res.ty,
+            });
+        }
+    }
+
+    let task_locals = app
+        .idle
+        .iter()
+        .map(|idle| (0, &idle.args.local_resources))
+        .chain(
+            app.hardware_tasks
+                .values()
+                .map(|task| (task.args.priority, &task.args.local_resources)),
+        )
+        .chain(
+            app.software_tasks
+                .values()
+                .map(|task| (task.args.priority, &task.args.local_resources)),
+        );
+
+    for (priority, locals) in task_locals {
+        for (name, _) in locals
+            .iter()
+            .filter(|(_, local)| matches!(local, TaskLocal::External))
+        {
+            let res = &app.local_resources[name];
+
+            if !has_link_section(&res.attrs) {
+                regions
+                    .entry(BTreeSet::from([priority]))
+                    .or_default()
+                    .push(Isolated {
+                        name: util::static_local_resource_ident(name),
+                        cfgs: &res.cfgs,
+                        ty: &res.ty,
+                    });
+            }
+        }
+    }
+
+    regions.into_iter().collect()
+}
+
+/// The static holding the storage of the resources isolated by MPU `region`
+fn isolation_storage_ident(region: usize) -> Ident {
+    util::mark_internal_name(&format!("mpu_storage{region}"))
+}
+
 pub fn interrupt_ident() -> Ident {
     let span = Span::call_site();
     Ident::new("interrupt", span)
@@ -42,9 +140,24 @@ pub fn interrupt_mod(app: &App) -> TokenStream2 {
 }
 
 pub fn check_stack_overflow_before_init(
-    _app: &App,
+    app: &App,
     _analysis: &CodegenAnalysis,
 ) -> Vec<TokenStream2> {
+    if let Some(size) = app.args.backend.as_ref().and_then(|b| b.stack_guard) {
+        return vec![quote!(
+            // Guard the bottom of the stack with the MPU, using symbols from `cortex-m-rt`.
+            extern "C" {
+                pub static _stack_start: u32;
+                pub static __ebss: u32;
+            }
+
+            let stack_start = &_stack_start as *const _ as u32;
+            let ebss = &__ebss as *const _ as u32;
+
+            rtic::export::mpu::stack_guard(ebss, stack_start, #size);
+        )];
+    }
+
     vec![quote!(
         // Check for stack overflow using symbols from `cortex-m-rt`.
         extern "C" {
@@ -287,7 +400,64 @@ pub fn pre_init_enable_interrupts(app: &App, analysis: &CodegenAnalysis) -> Vec<
     stmts
 }
 
-pub fn architecture_specific_analysis(app: &App, _: &SyntaxAnalysis) -> parse::Result<()> {
+pub fn resource_storage(
+    app: &App,
+    analysis: &CodegenAnalysis,
+    static_name: &Ident,
+) -> Option<TokenStream2> {
+    isolated_regions(app, analysis)
+        .iter()
+        .enumerate()
+        .find(|(_, (_, statics))| statics.iter().any(|res| res.name == *static_name))
+        .map(|(region, _)| {
+            let storage = isolation_storage_ident(region + 1);
+            quote!(&#storage.resources.#static_name)
+        })
+}
+
+pub fn post_init_enable_protection(app: &App, analysis: &CodegenAnalysis) -> Vec<TokenStream2> {
+    let regions = isolated_regions(app, analysis);
+
+    if regions.is_empty() {
+        return vec![];
+    }
+
+    // Deny access to the storage of each region, which is aligned to its size
+    let mut stmts = (1..=regions.len())
+        .map(|region| {
+            let storage = isolation_storage_ident(region);
+            let region = region as u8;
+
+            quote!(rtic::export::mpu::isolate(
+                #region,
+                ::core::ptr::addr_of!(#storage) as usize,
+                ::core::mem::size_of_val(&#storage),
+            );)
+        })
+        .collect::<Vec<_>>();
+
+    // `idle` runs at priority level 0
+    stmts.push(quote!(
+        rtic::export::mpu::enable();
+        rtic::export::mpu::switch(__rtic_internal_mpu_regions(0));
+    ));
+
+    stmts
+}
+
+pub fn architecture_specific_analysis(app: &App, analysis: &SyntaxAnalysis) -> parse::Result<()> {
+    // Check that the MPU has a region for every set of priority levels accessing isolated
+    // resources
+    let regions = isolated_regions(app, analysis);
+    if let Some((_, statics)) = regions.get(MPU_ISOLATION_REGIONS) {
+        return Err(parse::Error::new(
+            statics[0].name.span(),
+            format!(
+                "the isolated resources can be accessed by at most {MPU_ISOLATION_REGIONS} different sets of priority levels, one MPU region each"
+            ),
+        ));
+    }
+
     // Check that external (device-specific) interrupts are not named after known (Cortex-M)
     // exceptions
     for name in app.args.dispatchers.keys() {
@@ -348,17 +518,38 @@ pub fn architecture_specific_analysis(app: &App, _: &SyntaxAnalysis) -> parse::R
 
             _ => {}
         }
+
+        // The wakers of an async hardware task pend its interrupt through the NVIC
+        if task.is_async && is_exception(&task.args.binds) {
+            return Err(parse::Error::new(
+                name.span(),
+                "async hardware tasks can only be bound to device interrupts",
+            ));
+        }
     }
 
     Ok(())
 }
 
-pub fn interrupt_entry(_app: &App, _analysis: &CodegenAnalysis) -> Vec<TokenStream2> {
-    vec![]
+pub fn interrupt_entry(app: &App, analysis: &CodegenAnalysis) -> Vec<TokenStream2> {
+    if isolated_regions(app, analysis).is_empty() {
+        return vec![];
+    }
+
+    vec![quote!(
+        let __rtic_internal_mpu_previous =
+            rtic::export::mpu::switch(__rtic_internal_mpu_regions(PRIORITY));
+    )]
 }
 
-pub fn interrupt_exit(_app: &App, _analysis: &CodegenAnalysis) -> Vec<TokenStream2> {
-    vec![]
+pub fn interrupt_exit(app: &App, analysis: &CodegenAnalysis) -> Vec<TokenStream2> {
+    if isolated_regions(app, analysis).is_empty() {
+        return vec![];
+    }
+
+    vec![quote!(
+        rtic::export::mpu::switch(__rtic_internal_mpu_previous);
+    )]
 }
 
 pub fn async_entry(
@@ -392,6 +583,83 @@ pub fn handler_config(
     vec![]
 }
 
-pub fn extra_modules(_app: &App, _analysis: &SyntaxAnalysis) -> Vec<TokenStream2> {
-    vec![]
+pub fn extra_modules(app: &App, analysis: &SyntaxAnalysis) -> Vec<TokenStream2> {
+    let regions = isolated_regions(app, analysis);
+
+    if regions.is_empty() {
+        return vec![];
+    }
+
+    let mut items = vec![];
+    let mut masks: BTreeMap<u8, u8> = BTreeMap::new();
+
+    for (region, (levels, statics)) in regions.iter().enumerate() {
+        let region = region + 1;
+        let storage = isolation_storage_ident(region);
+        let storage_ty = util::mark_internal_name(&format!("MpuStorage{region}"));
+        let resources_ty = util::mark_internal_name(&format!("MpuResources{region}"));
+        let section = util::link_section_uninit();
+        let fields = statics.iter().map(|Isolated { name, cfgs, ty }| {
+            quote!(
+                #(#cfgs)*
+                #name: rtic::RacyCell<core::mem::MaybeUninit<#ty>>,
+            )
+        });
+        let inits = statics.iter().map(|Isolated { name, cfgs, .. }| {
+            quote!(
+                #(#cfgs)*
+                #name: rtic::RacyCell::new(core::mem::MaybeUninit::uninit()),
+            )
+        });
+
+        // The storage is aligned to its size rounded up to a power of two, so the MPU region
+        // covers exactly the isolated resources
+        items.push(quote!(
+            #[allow(non_camel_case_types)]
+            #[doc(hidden)]
+            #[repr(C)]
+            struct #resources_ty {
+                #(#fields)*
+            }
+
+            #[allow(non_camel_case_types)]
+            #[doc(hidden)]
+            #[repr(C)]
+            struct #storage_ty {
+                _align: [<rtic::export::mpu::Align<
+                    { rtic::export::mpu::region_size(core::mem::size_of::<#resources_ty>()) },
+                > as rtic::export::mpu::Alignment>::Type; 0],
+                resources: #resources_ty,
+            }
+
+            #[allow(non_upper_case_globals)]
+            #[doc(hidden)]
+            #section
+            static #storage: #storage_ty = #storage_ty {
+                _align: [],
+                resources: #resources_ty {
+                    #(#inits)*
+                },
+            };
+        ));
+
+        for level in levels {
+            *masks.entry(*level).or_default() |= 1 << region;
+        }
+    }
+
+    let arms = masks.iter().map(|(level, mask)| quote!(#level => #mask,));
+
+    items.push(quote!(
+        /// The mask of the MPU regions accessible to a priority level
+        #[doc(hidden)]
+        const fn __rtic_internal_mpu_regions(priority: u8) -> u8 {
+            match priority {
+                #(#arms)*
+                _ => 0,
+            }
+        }
+    ));
+
+    items
 }
@@ -5,7 +5,10 @@ pub use esp32c3::*;
 mod esp32c3 {
     use crate::{
         analyze::Analysis as CodegenAnalysis,
-        codegen::util,
+        codegen::{
+            bindings::riscv_common::{self, is_core_interrupt, is_core_source},
+            util,
+        },
         syntax::{analyze::Analysis as SyntaxAnalysis, ast::App},
     };
     use proc_macro2::{Span, TokenStream as TokenStream2};
@@ -61,12 +64,21 @@ mod esp32c3 {
         quote!(#device::#interrupt)
     }
 
-    pub fn extra_assertions(_: &App, _: &SyntaxAnalysis) -> Vec<TokenStream2> {
-        vec![]
+    pub fn extra_assertions(app: &App, _: &SyntaxAnalysis) -> Vec<TokenStream2> {
+        if !stack_paint(app) {
+            return vec![];
+        }
+
+        // The scan of `rtic::stack::record` grows with the free stack, and runs in a critical
+        // section at the end of every interrupt handler
+        vec![quote!(
+            #[cfg(not(debug_assertions))]
+            ::core::compile_error!("`stack_paint` adds a critical section proportional to the free stack to the end of every interrupt handler, it can only be used in builds with debug assertions");
+        )]
     }
 
-    pub fn pre_init_preprocessing(_app: &mut App, _analysis: &SyntaxAnalysis) -> parse::Result<()> {
-        Ok(())
+    pub fn pre_init_preprocessing(app: &mut App, _analysis: &SyntaxAnalysis) -> parse::Result<()> {
+        riscv_common::raise_core_source_priorities(app)
     }
 
     pub fn pre_init_checks(app: &App, _: &SyntaxAnalysis) -> Vec<TokenStream2> {
@@ -90,7 +102,8 @@ mod esp32c3 {
         for (&priority, name) in interrupt_ids.chain(
             app.hardware_tasks
                 .values()
-                .filter_map(|task| Some((&task.args.priority, &task.args.binds))),
+                .filter(|task| !is_core_source(&task.args.binds))
+                .map(|task| (&task.args.priority, &task.args.binds)),
         ) {
             let es = format!(
                 "Maximum priority used by interrupt vector '{name}' is more than supported by hardware"
@@ -111,6 +124,21 @@ mod esp32c3 {
         stmts
     }
 
+    pub fn resource_storage(
+        _app: &App,
+        _analysis: &CodegenAnalysis,
+        _static_name: &Ident,
+    ) -> Option<TokenStream2> {
+        None
+    }
+
+    pub fn post_init_enable_protection(
+        _app: &App,
+        _analysis: &CodegenAnalysis,
+    ) -> Vec<TokenStream2> {
+        vec![]
+    }
+
     pub fn architecture_specific_analysis(
         app: &App,
         _analysis: &SyntaxAnalysis,
@@ -157,21 +185,75 @@ mod esp32c3 {
             // "custom attribute panicked" due to unwrap on None
             return Err(parse::Error::new(first.unwrap().span(), s));
         }
-        Ok(())
+
+        // The wakers of an async hardware task pend its interrupt, which is only possible for
+        // the CPU interrupts
+        for (name, task) in &app.hardware_tasks {
+            if task.is_async
+                && !matches!(
+                    &*task.args.binds.to_string(),
+                    "FROM_CPU_INTR0" | "FROM_CPU_INTR1" | "FROM_CPU_INTR2" | "FROM_CPU_INTR3"
+                )
+            {
+                return Err(parse::Error::new(
+                    name.span(),
+                    "async hardware tasks can only be bound to FROM_CPU_INTRX",
+                ));
+            }
+        }
+
+        // The core local interrupts are not wired to the interrupt matrix of the ESP32-C3
+        if let Some(task) = app
+            .hardware_tasks
+            .values()
+            .find(|task| is_core_interrupt(&task.args.binds))
+        {
+            return Err(parse::Error::new(
+                task.args.binds.span(),
+                format!("`{}` is not available on the ESP32-C3", task.args.binds),
+            ));
+        }
+
+        riscv_common::check_core_sources(app)
     }
 
-    pub fn interrupt_entry(_app: &App, _analysis: &CodegenAnalysis) -> Vec<TokenStream2> {
-        vec![]
+    pub fn interrupt_entry(app: &App, _analysis: &CodegenAnalysis) -> Vec<TokenStream2> {
+        if stack_paint(app) {
+            // The stack usage of the handler is measured from here
+            vec![quote!(let __rtic_internal_entry_sp = rtic::export::read_sp();)]
+        } else {
+            vec![]
+        }
     }
 
-    pub fn interrupt_exit(_app: &App, _analysis: &CodegenAnalysis) -> Vec<TokenStream2> {
-        vec![]
+    pub fn interrupt_exit(app: &App, _analysis: &CodegenAnalysis) -> Vec<TokenStream2> {
+        if stack_paint(app) {
+            vec![quote!(rtic::stack::record(PRIORITY, __rtic_internal_entry_sp);)]
+        } else {
+            vec![]
+        }
+    }
+
+    fn stack_paint(app: &App) -> bool {
+        app.args
+            .backend
+            .as_ref()
+            .is_some_and(|backend| backend.stack.paint)
     }
 
     pub fn check_stack_overflow_before_init(
-        _app: &App,
+        app: &App,
         _analysis: &CodegenAnalysis,
     ) -> Vec<TokenStream2> {
+        let stack = app.args.backend.as_ref().map(|backend| &backend.stack);
+        let paint = stack.is_some_and(|stack| stack.paint);
+        let guard = stack.and_then(|stack| stack.guard).unwrap_or(0) as usize;
+
+        // Paint the stack and install the stack guard, see `rtic::stack`
+        let stack_init = (paint || guard > 0).then(|| {
+            quote!(rtic::stack::init(ebss as usize, stack_start as usize, #paint, #guard);)
+        });
+
         vec![quote!(
             // Check for stack overflow using symbols from `risc-v-rt`.
             extern "C" {
@@ -188,6 +270,8 @@ mod esp32c3 {
                     panic!("Stack overflow after allocating executors");
                 }
             }
+
+            #stack_init
         )]
     }
 
@@ -230,7 +314,8 @@ mod esp32c3 {
         for (_, name) in interrupt_ids.chain(
             app.hardware_tasks
                 .values()
-                .filter_map(|task| Some((&task.args.priority, &task.args.binds))),
+                .filter(|task| !is_core_source(&task.args.binds))
+                .map(|task| (&task.args.priority, &task.args.binds)),
         ) {
             if *name == dispatcher_name {
                 let ret = &("cpu_int_".to_owned() + &curr_cpu_id.to_string() + "_handler");
@@ -0,0 +1,116 @@
+//! Hardware tasks bound to the interrupts and exceptions that are handled by the RISC-V core
+//! instead of the interrupt controller of the backend
+
+use crate::syntax::ast::App;
+use syn::{parse, Ident};
+
+/// Synchronous exceptions, named after their `riscv-rt` handlers
+const EXCEPTIONS: &[&str] = &[
+    "InstructionMisaligned",
+    "InstructionFault",
+    "IllegalInstruction",
+    "Breakpoint",
+    "LoadMisaligned",
+    "LoadFault",
+    "StoreMisaligned",
+    "StoreFault",
+    "UserEnvCall",
+    "SupervisorEnvCall",
+    "MachineEnvCall",
+    "InstructionPageFault",
+    "LoadPageFault",
+    "StorePageFault",
+    // Default handler of all the exceptions
+    "ExceptionHandler",
+];
+
+/// Core local interrupts, named after their `riscv-rt` handlers
+const CORE_INTERRUPTS: &[&str] = &["MachineSoft", "MachineTimer"];
+
+pub fn is_exception(name: &Ident) -> bool {
+    EXCEPTIONS.contains(&&*name.to_string())
+}
+
+pub fn is_core_interrupt(name: &Ident) -> bool {
+    CORE_INTERRUPTS.contains(&&*name.to_string())
+}
+
+/// `true` if `name` is not handled by the interrupt controller of the backend
+pub fn is_core_source(name: &Ident) -> bool {
+    is_exception(name) || is_core_interrupt(name)
+}
+
+/// The interrupts and exceptions of the core are not masked by the priority threshold of the
+/// backend, they preempt every priority level. The hardware tasks bound to them run above the
+/// priority of all the other tasks, a `priority` argument must be that priority.
+pub fn raise_core_source_priorities(app: &mut App) -> parse::Result<()> {
+    let max = app
+        .hardware_tasks
+        .values()
+        .filter(|task| !is_core_source(&task.args.binds))
+        .map(|task| task.args.priority)
+        .chain(app.software_tasks.values().map(|task| task.args.priority))
+        .max()
+        .unwrap_or(0);
+
+    for task in app.hardware_tasks.values_mut() {
+        if is_core_source(&task.args.binds) {
+            let priority = max.checked_add(1).ok_or_else(|| {
+                parse::Error::new(
+                    task.args.binds.span(),
+                    format!(
+                        "`{}` must run above all the priority levels, priority {max} is the \
+                            highest possible",
+                        task.args.binds
+                    ),
+                )
+            })?;
+
+            if let Some(span) = task.args.priority_span {
+                if task.args.priority != priority {
+                    return Err(parse::Error::new(
+                        span,
+                        format!(
+                            "`{}` preempts all priority levels, the task runs at priority \
+                                {priority}; remove the `priority` argument",
+                            task.args.binds
+                        ),
+                    ));
+                }
+            }
+
+            task.args.priority = priority;
+        }
+    }
+
+    Ok(())
+}
+
+/// The hardware tasks bound to the core can not take part in the priority ceiling protocol
+pub fn check_core_sources(app: &App) -> parse::Result<()> {
+    for (name, task) in &app.hardware_tasks {
+        let binds = &task.args.binds;
+        if !is_core_source(binds) {
+            continue;
+        }
+
+        if task.is_async {
+            return Err(parse::Error::new(
+                name.span(),
+                format!("async hardware tasks can not be bound to `{binds}`"),
+            ));
+        }
+
+        if let Some(resource) = task.args.shared_resources.keys().next() {
+            return Err(parse::Error::new(
+                resource.span(),
+                format!(
+                    "`{binds}` preempts all priority levels, \
+                        the hardware tasks bound to it can not access shared resources"
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
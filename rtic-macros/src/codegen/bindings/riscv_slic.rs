@@ -1,3 +1,4 @@
+use super::riscv_common::{self, is_core_source};
 use crate::{
     analyze::Analysis as CodegenAnalysis,
     syntax::{
@@ -58,9 +59,18 @@ pub fn impl_mutex(
 }
 
 /// This macro is used to define additional compile-time assertions in case the platform needs it.
-/// The Cortex-M implementations do not use it. Thus, we think we do not need it either.
-pub fn extra_assertions(_app: &App, _analysis: &SyntaxAnalysis) -> Vec<TokenStream2> {
-    vec![]
+/// Here it rejects `stack_paint` in release builds.
+pub fn extra_assertions(app: &App, _analysis: &SyntaxAnalysis) -> Vec<TokenStream2> {
+    if !stack_paint(app) {
+        return vec![];
+    }
+
+    // The scan of `rtic::stack::record` grows with the free stack, and runs in a critical
+    // section at the end of every interrupt handler
+    vec![quote!(
+        #[cfg(not(debug_assertions))]
+        ::core::compile_error!("`stack_paint` adds a critical section proportional to the free stack to the end of every interrupt handler, it can only be used in builds with debug assertions");
+    )]
 }
 
 pub fn pre_init_preprocessing(app: &mut App, _analysis: &SyntaxAnalysis) -> parse::Result<()> {
@@ -87,7 +97,7 @@ pub fn pre_init_preprocessing(app: &mut App, _analysis: &SyntaxAnalysis) -> pars
             .insert(dispatcher_ident, Dispatcher { attrs: vec![] });
     }
 
-    Ok(())
+    riscv_common::raise_core_source_priorities(app)
 }
 
 /// This macro is used to check at run-time that all the interruption dispatchers exist.
@@ -116,6 +126,7 @@ pub fn pre_init_enable_interrupts(app: &App, analysis: &CodegenAnalysis) -> Vec<
     for (&p, name) in interrupt_ids.chain(
         app.hardware_tasks
             .values()
+            .filter(|task| !is_core_source(&task.args.binds))
             .map(|task| (&task.args.priority, &task.args.binds)),
     ) {
         stmts.push(quote!(
@@ -124,10 +135,44 @@ pub fn pre_init_enable_interrupts(app: &App, analysis: &CodegenAnalysis) -> Vec<
     }
     // Finally, we activate the interrupts
     stmts.push(quote!(rtic::export::set_interrupts();));
+    stmts.append(&mut enable_core_interrupts(app));
     stmts
 }
 
+/// Enables the core local interrupts bound to hardware tasks
+fn enable_core_interrupts(app: &App) -> Vec<TokenStream2> {
+    app.hardware_tasks
+        .values()
+        .filter_map(|task| {
+            let enable = match &*task.args.binds.to_string() {
+                "MachineSoft" => "set_msoft",
+                "MachineTimer" => "set_mtimer",
+                _ => return None,
+            };
+            let enable = Ident::new(enable, Span::call_site());
+            let cfgs = &task.cfgs;
+
+            Some(quote!(
+                #(#cfgs)*
+                rtic::export::mie::#enable();
+            ))
+        })
+        .collect()
+}
+
 /// Any additional checks that depend on the system architecture.
+pub fn resource_storage(
+    _app: &App,
+    _analysis: &CodegenAnalysis,
+    _static_name: &Ident,
+) -> Option<TokenStream2> {
+    None
+}
+
+pub fn post_init_enable_protection(_app: &App, _analysis: &CodegenAnalysis) -> Vec<TokenStream2> {
+    vec![]
+}
+
 pub fn architecture_specific_analysis(app: &App, _analysis: &SyntaxAnalysis) -> parse::Result<()> {
     // Check that there are enough external interrupts to dispatch the software tasks and the timer queue handler
     let mut first = None;
@@ -161,23 +206,60 @@ pub fn architecture_specific_analysis(app: &App, _analysis: &SyntaxAnalysis) ->
         ));
     }
 
-    Ok(())
+    // The SLIC dispatches its software interrupts from the machine software interrupt
+    if let Some(task) = app
+        .hardware_tasks
+        .values()
+        .find(|task| task.args.binds == "MachineSoft")
+    {
+        return Err(parse::Error::new(
+            task.args.binds.span(),
+            "`MachineSoft` is used by the SLIC to dispatch the tasks",
+        ));
+    }
+
+    riscv_common::check_core_sources(app)
 }
 
 /// Macro to add statements to be executed at the beginning of all the interrupt handlers.
-pub fn interrupt_entry(_app: &App, _analysis: &CodegenAnalysis) -> Vec<TokenStream2> {
-    vec![]
+pub fn interrupt_entry(app: &App, _analysis: &CodegenAnalysis) -> Vec<TokenStream2> {
+    if stack_paint(app) {
+        // The stack usage of the handler is measured from here
+        vec![quote!(let __rtic_internal_entry_sp = rtic::export::read_sp();)]
+    } else {
+        vec![]
+    }
 }
 
 /// Macro to add statements to be executed at the end of all the interrupt handlers.
-pub fn interrupt_exit(_app: &App, _analysis: &CodegenAnalysis) -> Vec<TokenStream2> {
-    vec![]
+pub fn interrupt_exit(app: &App, _analysis: &CodegenAnalysis) -> Vec<TokenStream2> {
+    if stack_paint(app) {
+        vec![quote!(rtic::stack::record(PRIORITY, __rtic_internal_entry_sp);)]
+    } else {
+        vec![]
+    }
+}
+
+fn stack_paint(app: &App) -> bool {
+    app.args
+        .backend
+        .as_ref()
+        .is_some_and(|backend| backend.stack.paint)
 }
 
 pub fn check_stack_overflow_before_init(
-    _app: &App,
+    app: &App,
     _analysis: &CodegenAnalysis,
 ) -> Vec<TokenStream2> {
+    let stack = app.args.backend.as_ref().map(|backend| &backend.stack);
+    let paint = stack.is_some_and(|stack| stack.paint);
+    let guard = stack.and_then(|stack| stack.guard).unwrap_or(0) as usize;
+
+    // Paint the stack and install the stack guard, see `rtic::stack`
+    let stack_init = (paint || guard > 0).then(|| {
+        quote!(rtic::stack::init(ebss as usize, stack_start as usize, #paint, #guard);)
+    });
+
     vec![quote!(
         // Check for stack overflow using symbols from `risc-v-rt`.
         extern "C" {
@@ -194,6 +276,8 @@ pub fn check_stack_overflow_before_init(
                 panic!("Stack overflow after allocating executors");
             }
         }
+
+        #stack_init
     )]
 }
 
@@ -237,6 +321,7 @@ pub fn extra_modules(app: &App, _analysis: &SyntaxAnalysis) -> Vec<TokenStream2>
         .hardware_tasks
         .values()
         .map(|task| &task.args.binds)
+        .filter(|binds| !is_core_source(binds))
         .collect();
     let sw_slice: Vec<_> = app.args.dispatchers.keys().collect();
 
@@ -46,6 +46,18 @@ pub fn pre_init_enable_interrupts(app: &App, analysis: &CodegenAnalysis) -> Vec<
     vec![]
 }
 
+pub fn resource_storage(
+    app: &App,
+    analysis: &CodegenAnalysis,
+    static_name: &Ident,
+) -> Option<TokenStream2> {
+    None
+}
+
+pub fn post_init_enable_protection(app: &App, analysis: &CodegenAnalysis) -> Vec<TokenStream2> {
+    vec![]
+}
+
 pub fn architecture_specific_analysis(app: &App, analysis: &SyntaxAnalysis) -> parse::Result<()> {
     Ok(())
 }
@@ -1,13 +1,17 @@
-use crate::syntax::{ast::App, Context};
+use crate::syntax::{
+    ast::{App, HardwareTask},
+    Context,
+};
 use crate::{
     analyze::Analysis,
     codegen::{
-        bindings::{interrupt_entry, interrupt_exit, handler_config},
-        local_resources_struct, module, shared_resources_struct,
+        bindings::{handler_config, interrupt_entry, interrupt_exit, interrupt_mod},
+        local_resources_struct, module, shared_resources_struct, util,
     },
 };
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
+use syn::Ident;
 
 /// Generate support code for hardware tasks (`#[exception]`s and `#[interrupt]`s)
 pub fn codegen(app: &App, analysis: &Analysis) -> TokenStream2 {
@@ -23,6 +27,42 @@ pub fn codegen(app: &App, analysis: &Analysis) -> TokenStream2 {
         let entry_stmts = interrupt_entry(app, analysis);
         let exit_stmts = interrupt_exit(app, analysis);
         let config = handler_config(app, analysis, symbol.clone());
+        let task_fn = util::task_fn_path(name, task.module.as_ref());
+
+        let run_task = if task.is_async {
+            let exec_name = util::internal_task_ident(name, "EXEC");
+            let int_mod = interrupt_mod(app);
+
+            mod_app.push(quote!(
+                #(#cfgs)*
+                #[allow(non_upper_case_globals)]
+                static #exec_name: rtic::export::executor::AsyncTaskExecutorPtr =
+                    rtic::export::executor::AsyncTaskExecutorPtr::new();
+            ));
+
+            if let Some(section) = util::executor_section(app, &task.args.section) {
+                mod_app.push(util::executor_storage(name, section, &task_fn, 0, cfgs));
+            }
+
+            // The task is (re)started by the first interrupt after it has completed, every
+            // interrupt polls it and its wakers pend the interrupt
+            quote!(
+                let exec = rtic::export::executor::AsyncTaskExecutor::from_ptr_1_args(#task_fn, &#exec_name);
+                if exec.try_allocate() {
+                    exec.spawn(#task_fn(#name::Context::new()));
+                }
+                exec.set_pending();
+                exec.poll(|| {
+                    rtic::export::pend(#int_mod::#symbol);
+                });
+            )
+        } else {
+            quote!(
+                #task_fn(
+                    #name::Context::new()
+                )
+            )
+        };
 
         mod_app.push(quote!(
             #[allow(non_snake_case)]
@@ -36,9 +76,7 @@ pub fn codegen(app: &App, analysis: &Analysis) -> TokenStream2 {
                 const PRIORITY: u8 = #priority;
 
                 rtic::export::run(PRIORITY, || {
-                    #name(
-                        #name::Context::new()
-                    )
+                    #run_task
                 });
 
                 #(#exit_stmts)*
@@ -58,7 +96,7 @@ pub fn codegen(app: &App, analysis: &Analysis) -> TokenStream2 {
         // `${task}Resources`
         if !task.args.shared_resources.is_empty() {
             let (item, constructor) =
-                shared_resources_struct::codegen(Context::HardwareTask(name), app);
+                shared_resources_struct::codegen(Context::HardwareTask(name), app, analysis);
 
             root.push(item);
 
@@ -71,20 +109,9 @@ pub fn codegen(app: &App, analysis: &Analysis) -> TokenStream2 {
 
         // End module generation
 
-        if !task.is_extern {
-            let attrs = &task.attrs;
-            let context = &task.context;
-            let stmts = &task.stmts;
-            user_tasks.push(quote!(
-                #(#attrs)*
-                #[allow(non_snake_case)]
-                fn #name(#context: #name::Context) {
-                    use rtic::Mutex as _;
-                    use rtic::mutex::prelude::*;
-
-                    #(#stmts)*
-                }
-            ));
+        // Tasks declared in a task module are generated in that module
+        if !task.is_extern && task.module.is_none() {
+            user_tasks.push(user_task(name, task));
         }
     }
 
@@ -96,3 +123,37 @@ pub fn codegen(app: &App, analysis: &Analysis) -> TokenStream2 {
         #(#user_tasks)*
     )
 }
+
+/// Generates the user provided function of a hardware task
+pub fn user_task(name: &Ident, task: &HardwareTask) -> TokenStream2 {
+    let attrs = &task.attrs;
+    let context = &task.context;
+    let stmts = &task.stmts;
+
+    // The function is called from the `#[app]` module
+    let vis = task.module.as_ref().map(|_| quote!(pub(super)));
+
+    if task.is_async {
+        quote!(
+            #(#attrs)*
+            #[allow(non_snake_case)]
+            #vis async fn #name<'a>(#context: #name::Context<'a>) {
+                use rtic::Mutex as _;
+                use rtic::mutex::prelude::*;
+
+                #(#stmts)*
+            }
+        )
+    } else {
+        quote!(
+            #(#attrs)*
+            #[allow(non_snake_case)]
+            #vis fn #name(#context: #name::Context) {
+                use rtic::Mutex as _;
+                use rtic::mutex::prelude::*;
+
+                #(#stmts)*
+            }
+        )
+    }
+}
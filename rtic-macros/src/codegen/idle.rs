@@ -15,7 +15,7 @@ pub fn codegen(app: &App, analysis: &Analysis) -> TokenStream2 {
         let name = &idle.name;
 
         if !idle.args.shared_resources.is_empty() {
-            let (item, constructor) = shared_resources_struct::codegen(Context::Idle, app);
+            let (item, constructor) = shared_resources_struct::codegen(Context::Idle, app, analysis);
 
             root_idle.push(item);
             mod_app.push(constructor);
@@ -3,10 +3,12 @@ use crate::{analyze::Analysis, codegen::util};
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
 
+use super::bindings::resource_storage;
+
 /// Generates `local` variables and local resource proxies
 ///
 /// I.e. the `static` variables and theirs proxies.
-pub fn codegen(app: &App, _analysis: &Analysis) -> TokenStream2 {
+pub fn codegen(app: &App, analysis: &Analysis) -> TokenStream2 {
     let mut mod_app = vec![];
 
     // All local resources declared in the `#[local]' struct
@@ -18,7 +20,8 @@ pub fn codegen(app: &App, _analysis: &Analysis) -> TokenStream2 {
         let attrs = &res.attrs;
 
         // late resources in `util::link_section_uninit`
-        // unless user specifies custom link section
+        // unless user specifies custom link section or the backend provides their storage
+        let storage = resource_storage(app, analysis, &mangled_name);
         let section = if attrs
             .iter()
             .any(|attr| attr.path().is_ident("link_section"))
@@ -30,16 +33,27 @@ pub fn codegen(app: &App, _analysis: &Analysis) -> TokenStream2 {
 
         // For future use
         // let doc = format!(" RTIC internal: {}:{}", file!(), line!());
-        mod_app.push(quote!(
-            #[allow(non_camel_case_types)]
-            #[allow(non_upper_case_globals)]
-            // #[doc = #doc]
-            #[doc(hidden)]
-            #(#attrs)*
-            #(#cfgs)*
-            #section
-            static #mangled_name: rtic::RacyCell<core::mem::MaybeUninit<#ty>> = rtic::RacyCell::new(core::mem::MaybeUninit::uninit());
-        ));
+        mod_app.push(if let Some(storage) = storage {
+            quote!(
+                #[allow(non_camel_case_types)]
+                #[allow(non_upper_case_globals)]
+                #[doc(hidden)]
+                #(#attrs)*
+                #(#cfgs)*
+                static #mangled_name: &rtic::RacyCell<core::mem::MaybeUninit<#ty>> = #storage;
+            )
+        } else {
+            quote!(
+                #[allow(non_camel_case_types)]
+                #[allow(non_upper_case_globals)]
+                // #[doc = #doc]
+                #[doc(hidden)]
+                #(#attrs)*
+                #(#cfgs)*
+                #section
+                static #mangled_name: rtic::RacyCell<core::mem::MaybeUninit<#ty>> = rtic::RacyCell::new(core::mem::MaybeUninit::uninit());
+            )
+        });
     }
 
     // All declared `local = [NAME: TY = EXPR]` local resources
@@ -30,13 +30,48 @@ pub fn codegen(app: &App, analysis: &Analysis) -> TokenStream2 {
 
     let mut executor_allocations = Vec::new();
 
-    for (name, _) in app.software_tasks.iter() {
+    for (name, task) in app.software_tasks.iter() {
         let exec_name = util::internal_task_ident(name, "EXEC");
-        let new_n_args = util::new_n_args_ident(app.software_tasks[name].inputs.len());
+        let new_n_args = util::new_n_args_ident(task.inputs.len());
+        let task_fn = util::task_fn_path(name, task.module.as_ref());
+
+        if util::executor_section(app, &task.args.section).is_some() {
+            let storage_name = util::internal_task_ident(name, "EXEC_STORAGE");
+
+            executor_allocations.push(quote!(
+                #exec_name.set_in_storage(&#storage_name, rtic::export::executor::AsyncTaskExecutor::#new_n_args(#task_fn));
+            ));
+            continue;
+        }
+
+        executor_allocations.push(quote!(
+            let executor = ::core::mem::ManuallyDrop::new(rtic::export::executor::AsyncTaskExecutor::#new_n_args(#task_fn));
+            executors_size += ::core::mem::size_of_val(&executor);
+            #exec_name.set_in_main(&executor);
+        ));
+    }
+
+    for (name, task) in app.hardware_tasks.iter().filter(|(_, task)| task.is_async) {
+        let exec_name = util::internal_task_ident(name, "EXEC");
+        let task_fn = util::task_fn_path(name, task.module.as_ref());
+        let cfgs = &task.cfgs;
+
+        if util::executor_section(app, &task.args.section).is_some() {
+            let storage_name = util::internal_task_ident(name, "EXEC_STORAGE");
+
+            executor_allocations.push(quote!(
+                #(#cfgs)*
+                #exec_name.set_in_storage(&#storage_name, rtic::export::executor::AsyncTaskExecutor::new_1_args(#task_fn));
+            ));
+            continue;
+        }
 
         executor_allocations.push(quote!(
-            let executor = ::core::mem::ManuallyDrop::new(rtic::export::executor::AsyncTaskExecutor::#new_n_args(#name));
+            #(#cfgs)*
+            let executor = ::core::mem::ManuallyDrop::new(rtic::export::executor::AsyncTaskExecutor::new_1_args(#task_fn));
+            #(#cfgs)*
             executors_size += ::core::mem::size_of_val(&executor);
+            #(#cfgs)*
             #exec_name.set_in_main(&executor);
         ));
     }
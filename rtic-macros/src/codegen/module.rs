@@ -111,6 +111,8 @@ pub fn codegen(ctxt: Context, app: &App, analysis: &Analysis) -> TokenStream2 {
 
     let internal_context_name = util::internal_task_ident(name, "Context");
     let exec_name = util::internal_task_ident(name, "EXEC");
+    let name_s = name.to_string();
+    let priority = ctxt.priority(app);
 
     items.push(quote!(
         #(#cfgs)*
@@ -133,6 +135,18 @@ pub fn codegen(ctxt: Context, app: &App, analysis: &Analysis) -> TokenStream2 {
                     #(#values,)*
                 }
             }
+
+            /// The priority this context runs at
+            #[inline(always)]
+            pub const fn priority(&self) -> u8 {
+                #priority
+            }
+
+            /// The name of this task
+            #[inline(always)]
+            pub const fn name(&self) -> &'static str {
+                #name_s
+            }
         }
     ));
 
@@ -158,6 +172,37 @@ pub fn codegen(ctxt: Context, app: &App, analysis: &Analysis) -> TokenStream2 {
         };
 
         let internal_spawn_ident = util::internal_task_ident(name, "spawn");
+        let task_fn = util::task_fn_path(name, spawnee.module.as_ref());
+
+        // With an app monotonic, the task records when it is spawned
+        let record_spawn_instant = app.args.monotonic.as_ref().map(|monotonic| {
+            let spawn_instant_name = util::internal_task_ident(name, "SPAWN_INSTANT");
+
+            items.push(quote!(
+                #(#cfgs)*
+                #[allow(non_upper_case_globals)]
+                #[doc(hidden)]
+                static #spawn_instant_name: rtic::RacyCell<
+                    Option<<#monotonic as rtic::export::Monotonic>::Instant>,
+                > = rtic::RacyCell::new(None);
+
+                #(#cfgs)*
+                impl<'a> #internal_context_name<'a> {
+                    /// The instant this task was spawned at, on the app monotonic
+                    #[inline(always)]
+                    pub fn spawn_instant(&self) -> <#monotonic as rtic::export::Monotonic>::Instant {
+                        // SAFETY: Written by `spawn` before the task runs
+                        unsafe { (*#spawn_instant_name.get()).unwrap_unchecked() }
+                    }
+                }
+            ));
+
+            quote!(
+                #spawn_instant_name
+                    .get_mut()
+                    .write(Some(<#monotonic as rtic::export::Monotonic>::now()));
+            )
+        });
         let from_ptr_n_args = util::from_ptr_n_args_ident(spawnee.inputs.len());
         let (input_args, input_tupled, input_untupled, input_ty) =
             util::regroup_inputs(&spawnee.inputs);
@@ -171,9 +216,10 @@ pub fn codegen(ctxt: Context, app: &App, analysis: &Analysis) -> TokenStream2 {
             pub fn #internal_spawn_ident(#(#input_args,)*) -> Result<(), #input_ty> {
                 // SAFETY: If `try_allocate` succeeds one must call `spawn`, which we do.
                 unsafe {
-                    let exec = rtic::export::executor::AsyncTaskExecutor::#from_ptr_n_args(#name, &#exec_name);
+                    let exec = rtic::export::executor::AsyncTaskExecutor::#from_ptr_n_args(#task_fn, &#exec_name);
                     if exec.try_allocate() {
-                        exec.spawn(#name(unsafe { #name::Context::new() } #(,#input_untupled)*));
+                        #record_spawn_instant
+                        exec.spawn(#task_fn(unsafe { #name::Context::new() } #(,#input_untupled)*));
                         #pend_interrupt
 
                         Ok(())
@@ -189,6 +235,29 @@ pub fn codegen(ctxt: Context, app: &App, analysis: &Analysis) -> TokenStream2 {
             #[doc(inline)]
             pub use super::#internal_spawn_ident as spawn;
         ));
+
+        // Spawn entry point for C code, e.g. callbacks of C libraries
+        if spawnee.args.export_c {
+            let internal_export_c_ident = util::internal_task_ident(name, "export_c");
+            let symbol = spawnee
+                .args
+                .export_c_symbol
+                .as_ref()
+                .map_or_else(|| format!("{name}_spawn"), |symbol| symbol.value());
+            let (input_args, _, input_untupled, _) = util::regroup_inputs(&spawnee.inputs);
+
+            items.push(quote!(
+                #(#cfgs)*
+                /// Spawns the task from C, returns `false` if the task is already running
+                #[allow(non_snake_case)]
+                #[deny(improper_ctypes_definitions)]
+                #[doc(hidden)]
+                #[export_name = #symbol]
+                pub extern "C" fn #internal_export_c_ident(#(#input_args,)*) -> bool {
+                    #internal_spawn_ident(#(#input_untupled,)*).is_ok()
+                }
+            ));
+        }
     }
 
     if items.is_empty() {
@@ -1,4 +1,8 @@
-use crate::{analyze::Analysis, codegen::util, syntax::ast::App};
+use crate::{
+    analyze::Analysis,
+    codegen::{bindings, util},
+    syntax::ast::App,
+};
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
 
@@ -40,6 +44,9 @@ pub fn codegen(app: &App, analysis: &Analysis) -> Vec<TokenStream2> {
         }
     }
 
+    // Protect the memory of the priority levels, if the backend supports it
+    stmts.extend(bindings::post_init_enable_protection(app, analysis));
+
     // Enable the interrupts -- this completes the `init`-ialization phase
     stmts.push(quote!(rtic::export::interrupt::enable();));
 
@@ -0,0 +1,125 @@
+use crate::codegen::util;
+use crate::syntax::ast::App;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+
+/// Generates the runtime task registry, i.e. the table of software tasks with their status and
+/// `spawn_by_id`
+pub fn codegen(app: &App) -> TokenStream2 {
+    if !app.args.registry {
+        return quote!();
+    }
+
+    let mut infos = vec![];
+    let mut is_running_arms = vec![];
+    let mut spawn_arms = vec![];
+
+    for (id, (name, task)) in app.software_tasks.iter().enumerate() {
+        let name_s = name.to_string();
+        let priority = task.args.priority;
+        let spawn_by_id = task.args.spawn_by_id;
+
+        infos.push(quote!(
+            rtic::registry::TaskInfo {
+                id: #id,
+                name: #name_s,
+                priority: #priority,
+                spawn_by_id: #spawn_by_id,
+            }
+        ));
+
+        let exec_name = util::internal_task_ident(name, "EXEC");
+        let from_ptr_n_args = util::from_ptr_n_args_ident(task.inputs.len());
+        let task_fn = util::task_fn_path(name, task.module.as_ref());
+
+        is_running_arms.push(quote!(
+            #id => Some(unsafe {
+                rtic::export::executor::AsyncTaskExecutor::#from_ptr_n_args(#task_fn, &#exec_name)
+            }.is_running()),
+        ));
+
+        if spawn_by_id {
+            let internal_spawn_ident = util::internal_task_ident(name, "spawn");
+            let (_, _, input_untupled, _) = util::regroup_inputs(&task.inputs);
+            let tys = task.inputs.iter().map(|input| &input.ty);
+
+            // The arguments are decoded one after the other, all bytes must be used
+            spawn_arms.push(quote!(
+                #id => {
+                    #(
+                        let (#input_untupled, bytes) =
+                            <#tys as rtic::registry::FromBytes>::from_bytes(bytes)
+                                .ok_or(rtic::registry::SpawnByIdError::InvalidArguments)?;
+                    )*
+
+                    if !bytes.is_empty() {
+                        return Err(rtic::registry::SpawnByIdError::InvalidArguments);
+                    }
+
+                    #internal_spawn_ident(#(#input_untupled,)*)
+                        .map_err(|_| rtic::registry::SpawnByIdError::Busy)
+                }
+            ));
+        } else {
+            spawn_arms.push(quote!(
+                #id => Err(rtic::registry::SpawnByIdError::NotSpawnable),
+            ));
+        }
+    }
+
+    let n = infos.len();
+    let tasks_ident = util::mark_internal_name("registry_TASKS");
+    let find_ident = util::mark_internal_name("registry_find");
+    let is_running_ident = util::mark_internal_name("registry_is_running");
+    let spawn_by_id_ident = util::mark_internal_name("registry_spawn_by_id");
+
+    quote!(
+        #[doc(hidden)]
+        #[allow(non_upper_case_globals)]
+        pub static #tasks_ident: [rtic::registry::TaskInfo; #n] = [#(#infos,)*];
+
+        #[doc(hidden)]
+        pub fn #find_ident(name: &str) -> Option<&'static rtic::registry::TaskInfo> {
+            #tasks_ident.iter().find(|task| task.name == name)
+        }
+
+        #[doc(hidden)]
+        pub fn #is_running_ident(id: usize) -> Option<bool> {
+            match id {
+                #(#is_running_arms)*
+                _ => None,
+            }
+        }
+
+        #[doc(hidden)]
+        #[allow(unused_variables)]
+        pub fn #spawn_by_id_ident(
+            id: usize,
+            bytes: &[u8],
+        ) -> Result<(), rtic::registry::SpawnByIdError> {
+            match id {
+                #(#spawn_arms)*
+                _ => Err(rtic::registry::SpawnByIdError::UnknownTask),
+            }
+        }
+
+        /// Runtime registry of the software tasks
+        pub mod registry {
+            /// The software tasks, indexed by their ID
+            #[doc(inline)]
+            pub use super::#tasks_ident as TASKS;
+
+            /// Finds a task by its name
+            #[doc(inline)]
+            pub use super::#find_ident as find;
+
+            /// Returns if the task with this ID is running, `None` if there is no such task
+            #[doc(inline)]
+            pub use super::#is_running_ident as is_running;
+
+            /// Spawns the task with this ID, decoding its arguments from `bytes`
+            #[doc(inline)]
+            pub use super::#spawn_by_id_ident as spawn_by_id;
+        }
+    )
+}
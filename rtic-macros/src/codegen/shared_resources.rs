@@ -3,7 +3,7 @@ use crate::{analyze::Analysis, codegen::util};
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
 
-use super::bindings::impl_mutex;
+use super::bindings::{impl_mutex, resource_storage};
 
 /// Generates `static` variables and shared resource proxies
 pub fn codegen(app: &App, analysis: &Analysis) -> TokenStream2 {
@@ -18,7 +18,8 @@ pub fn codegen(app: &App, analysis: &Analysis) -> TokenStream2 {
         let attrs = &res.attrs;
 
         // late resources in `util::link_section_uninit`
-        // unless user specifies custom link section
+        // unless user specifies custom link section or the backend provides their storage
+        let storage = resource_storage(app, analysis, mangled_name);
         let section = if attrs
             .iter()
             .any(|attr| attr.path().is_ident("link_section"))
@@ -30,16 +31,27 @@ pub fn codegen(app: &App, analysis: &Analysis) -> TokenStream2 {
 
         // For future use
         // let doc = format!(" RTIC internal: {}:{}", file!(), line!());
-        mod_app.push(quote!(
-            #[allow(non_camel_case_types)]
-            #[allow(non_upper_case_globals)]
-            // #[doc = #doc]
-            #[doc(hidden)]
-            #(#attrs)*
-            #(#cfgs)*
-            #section
-            static #mangled_name: rtic::RacyCell<core::mem::MaybeUninit<#ty>> = rtic::RacyCell::new(core::mem::MaybeUninit::uninit());
-        ));
+        mod_app.push(if let Some(storage) = storage {
+            quote!(
+                #[allow(non_camel_case_types)]
+                #[allow(non_upper_case_globals)]
+                #[doc(hidden)]
+                #(#attrs)*
+                #(#cfgs)*
+                static #mangled_name: &rtic::RacyCell<core::mem::MaybeUninit<#ty>> = #storage;
+            )
+        } else {
+            quote!(
+                #[allow(non_camel_case_types)]
+                #[allow(non_upper_case_globals)]
+                // #[doc = #doc]
+                #[doc(hidden)]
+                #(#attrs)*
+                #(#cfgs)*
+                #section
+                static #mangled_name: rtic::RacyCell<core::mem::MaybeUninit<#ty>> = rtic::RacyCell::new(core::mem::MaybeUninit::uninit());
+            )
+        });
 
         // For future use
         // let doc = format!(" RTIC internal: {}:{}", file!(), line!());
@@ -79,16 +91,176 @@ pub fn codegen(app: &App, analysis: &Analysis) -> TokenStream2 {
             // For future use
             // let doc = format!(" RTIC internal ({} resource): {}:{}", doc, file!(), line!());
 
-            mod_app.push(impl_mutex(
-                app,
-                analysis,
-                cfgs,
-                true,
-                &shared_name,
-                &quote!(#ty),
-                ceiling,
-                &ptr,
-            ));
+            if res.properties.async_lock {
+                let lock_name = util::mark_internal_name(&format!("async_lock_{name}"));
+                let raw_name = util::raw_lock_ident(name);
+
+                // The ceiling based lock of the resource, wrapped to check the async lock
+                mod_resources.push(quote!(
+                    #[doc(hidden)]
+                    #[allow(non_camel_case_types)]
+                    #(#cfgs)*
+                    pub struct #raw_name<'a> {
+                        __rtic_internal_p: ::core::marker::PhantomData<&'a ()>,
+                    }
+
+                    #(#cfgs)*
+                    impl<'a> #raw_name<'a> {
+                        #[inline(always)]
+                        pub unsafe fn new() -> Self {
+                            #raw_name { __rtic_internal_p: ::core::marker::PhantomData }
+                        }
+                    }
+                ));
+
+                mod_app.push(impl_mutex(
+                    app,
+                    analysis,
+                    cfgs,
+                    true,
+                    &raw_name,
+                    &quote!(#ty),
+                    ceiling,
+                    &ptr,
+                ));
+
+                mod_app.push(quote!(
+                    #[allow(non_upper_case_globals)]
+                    #[doc(hidden)]
+                    #(#cfgs)*
+                    static #lock_name: rtic::async_lock::AsyncLock = rtic::async_lock::AsyncLock::new();
+
+                    #(#cfgs)*
+                    impl<'a> rtic::Mutex for shared_resources::#shared_name<'a> {
+                        type T = #ty;
+
+                        #[inline(always)]
+                        fn lock<RTIC_INTERNAL_R>(&mut self, f: impl FnOnce(&mut #ty) -> RTIC_INTERNAL_R) -> RTIC_INTERNAL_R {
+                            // Waiting at the ceiling would deadlock, the holder of the guard can
+                            // not run
+                            match self.try_lock(f) {
+                                Some(r) => r,
+                                None => panic!("`lock` of an `#[async_lock]` resource that is held by `async_lock`"),
+                            }
+                        }
+                    }
+
+                    #(#cfgs)*
+                    impl<'a> shared_resources::#shared_name<'a> {
+                        /// Locks the resource for the duration of `f`, returns `None` without
+                        /// calling `f` if the resource is held by `async_lock`
+                        #[inline(always)]
+                        pub fn try_lock<RTIC_INTERNAL_R>(&mut self, f: impl FnOnce(&mut #ty) -> RTIC_INTERNAL_R) -> Option<RTIC_INTERNAL_R> {
+                            let mut raw = unsafe { shared_resources::#raw_name::new() };
+
+                            rtic::Mutex::lock(&mut raw, |value| {
+                                // At the ceiling no other task that can take the async lock runs
+                                if #lock_name.is_taken() {
+                                    None
+                                } else {
+                                    Some(f(value))
+                                }
+                            })
+                        }
+
+                        /// Locks the resource until the returned guard is dropped, also across
+                        /// `.await` points
+                        #[inline(always)]
+                        pub async fn async_lock(&mut self) -> rtic::async_lock::AsyncLockGuard<'_, #ty> {
+                            unsafe { #lock_name.lock((*#mangled_name.get_mut()).as_mut_ptr()).await }
+                        }
+                    }
+                ));
+            } else if analysis.seqlocks.contains(name) {
+                let seqlock_name = util::mark_internal_name(&format!("seqlock_{name}"));
+                let raw_name = util::raw_lock_ident(name);
+                let read_name = util::seqlock_read_ident(name);
+
+                // The ceiling based lock of the resource, wrapped to mark the writes
+                mod_resources.push(quote!(
+                    #[doc(hidden)]
+                    #[allow(non_camel_case_types)]
+                    #(#cfgs)*
+                    pub struct #raw_name<'a> {
+                        __rtic_internal_p: ::core::marker::PhantomData<&'a ()>,
+                    }
+
+                    #(#cfgs)*
+                    impl<'a> #raw_name<'a> {
+                        #[inline(always)]
+                        pub unsafe fn new() -> Self {
+                            #raw_name { __rtic_internal_p: ::core::marker::PhantomData }
+                        }
+                    }
+
+                    #[doc(hidden)]
+                    #[allow(non_camel_case_types)]
+                    #(#cfgs)*
+                    pub struct #read_name<'a> {
+                        __rtic_internal_p: ::core::marker::PhantomData<&'a ()>,
+                    }
+
+                    #(#cfgs)*
+                    impl<'a> #read_name<'a> {
+                        #[inline(always)]
+                        pub unsafe fn new() -> Self {
+                            #read_name { __rtic_internal_p: ::core::marker::PhantomData }
+                        }
+                    }
+                ));
+
+                mod_app.push(impl_mutex(
+                    app,
+                    analysis,
+                    cfgs,
+                    true,
+                    &raw_name,
+                    &quote!(#ty),
+                    ceiling,
+                    &ptr,
+                ));
+
+                mod_app.push(quote!(
+                    #[allow(non_upper_case_globals)]
+                    #[doc(hidden)]
+                    #(#cfgs)*
+                    static #seqlock_name: rtic::seqlock::SeqLock = rtic::seqlock::SeqLock::new();
+
+                    #(#cfgs)*
+                    impl<'a> rtic::Mutex for shared_resources::#shared_name<'a> {
+                        type T = #ty;
+
+                        #[inline(always)]
+                        fn lock<RTIC_INTERNAL_R>(&mut self, f: impl FnOnce(&mut #ty) -> RTIC_INTERNAL_R) -> RTIC_INTERNAL_R {
+                            let mut raw = unsafe { shared_resources::#raw_name::new() };
+
+                            rtic::Mutex::lock(&mut raw, |value| unsafe {
+                                #seqlock_name.write(value as *mut #ty, f)
+                            })
+                        }
+                    }
+
+                    #(#cfgs)*
+                    impl<'a> shared_resources::#read_name<'a> {
+                        /// Copies the resource out, without locking it
+                        #[inline(always)]
+                        pub fn read(&self) -> #ty {
+                            unsafe { #seqlock_name.read((*#mangled_name.get()).as_ptr()) }
+                        }
+                    }
+                ));
+            } else {
+                mod_app.push(impl_mutex(
+                    app,
+                    analysis,
+                    cfgs,
+                    true,
+                    &shared_name,
+                    &quote!(#ty),
+                    ceiling,
+                    &ptr,
+                ));
+            }
         }
     }
 
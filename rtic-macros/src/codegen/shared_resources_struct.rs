@@ -1,11 +1,10 @@
 use crate::syntax::{ast::App, Context};
+use crate::{analyze::Analysis, codegen::util};
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
 
-use crate::codegen::util;
-
 /// Generate shared resources structs
-pub fn codegen(ctxt: Context, app: &App) -> (TokenStream2, TokenStream2) {
+pub fn codegen(ctxt: Context, app: &App, analysis: &Analysis) -> (TokenStream2, TokenStream2) {
     let resources = match ctxt {
         Context::Init => unreachable!("Tried to generate shared resources struct for init"),
         Context::Idle => {
@@ -50,6 +49,23 @@ pub fn codegen(ctxt: Context, app: &App) -> (TokenStream2, TokenStream2) {
                 #[allow(missing_docs)]
                 pub #name: &#lt #mut_ #ty
             ));
+        } else if access.is_shared()
+            && analysis.seqlock_read(name, ctxt.priority(app), ctxt.is_async(app))
+        {
+            let read_name = util::seqlock_read_ident(name);
+
+            fields.push(quote!(
+                #(#cfgs)*
+                #[allow(missing_docs)]
+                pub #name: shared_resources::#read_name<'a>
+            ));
+
+            values.push(quote!(
+                #(#cfgs)*
+                #name: shared_resources::#read_name::new()
+            ));
+
+            continue;
         } else if access.is_shared() {
             fields.push(quote!(
                 #(#cfgs)*
@@ -117,3 +133,99 @@ pub fn codegen(ctxt: Context, app: &App) -> (TokenStream2, TokenStream2) {
 
     (item, constructor)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyze;
+    use proc_macro2::TokenStream;
+    use quote::format_ident;
+
+    /// Fields of the shared resources struct of `reader`, in an app where `x: u32` is read by
+    /// `reader` and written by `writer` at the given priorities
+    fn reader_fields(reader: TokenStream, writer_priority: u8) -> String {
+        let writer_priority = proc_macro2::Literal::u8_unsuffixed(writer_priority);
+        let (app, analysis) = crate::syntax::parse2(
+            quote!(device = mock, dispatchers = [EXTI0]),
+            quote!(
+                mod app {
+                    #[shared]
+                    struct Shared {
+                        x: u32,
+                    }
+
+                    #[local]
+                    struct Local {}
+
+                    #[init]
+                    fn init(_: init::Context) -> (Shared, Local) {
+                        (Shared { x: 0 }, Local {})
+                    }
+
+                    #reader
+
+                    #[task(binds = UART1, priority = #writer_priority, shared = [x])]
+                    fn writer(_: writer::Context) {}
+                }
+            ),
+        )
+        .unwrap();
+        let analysis = analyze::app(analysis, &app);
+
+        let reader = format_ident!("reader");
+        let ctxt = if app.hardware_tasks.contains_key(&reader) {
+            Context::HardwareTask(&reader)
+        } else {
+            Context::SoftwareTask(&reader)
+        };
+
+        codegen(ctxt, &app, &analysis).0.to_string()
+    }
+
+    #[test]
+    fn sync_reader_above_writers_gets_a_reference() {
+        for writer_priority in [1, 2] {
+            let fields = reader_fields(
+                quote!(
+                    #[task(binds = UART0, priority = 2, shared = [&x])]
+                    fn reader(_: reader::Context) {}
+                ),
+                writer_priority,
+            );
+
+            assert!(fields.contains("pub x : & 'a u32"), "{fields}");
+        }
+    }
+
+    #[test]
+    fn sync_reader_below_a_writer_reads_through_the_seqlock() {
+        let fields = reader_fields(
+            quote!(
+                #[task(binds = UART0, priority = 1, shared = [&x])]
+                fn reader(_: reader::Context) {}
+            ),
+            2,
+        );
+
+        assert!(
+            fields.contains("pub x : shared_resources :: x_seqlock_read < 'a >"),
+            "{fields}"
+        );
+    }
+
+    #[test]
+    fn async_reader_reads_through_the_seqlock() {
+        let fields = reader_fields(
+            quote!(
+                #[task(priority = 2, shared = [&x])]
+                async fn reader(_: reader::Context) {}
+            ),
+            1,
+        );
+
+        assert!(
+            fields.contains("pub x : shared_resources :: x_seqlock_read < 'a >"),
+            "{fields}"
+        );
+    }
+}
@@ -1,10 +1,14 @@
-use crate::syntax::{ast::App, Context};
+use crate::syntax::{
+    ast::{App, SoftwareTask},
+    Context,
+};
 use crate::{
     analyze::Analysis,
     codegen::{local_resources_struct, module, shared_resources_struct},
 };
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
+use syn::Ident;
 
 pub fn codegen(app: &App, analysis: &Analysis) -> TokenStream2 {
     let mut mod_app = vec![];
@@ -24,31 +28,16 @@ pub fn codegen(app: &App, analysis: &Analysis) -> TokenStream2 {
 
         if !task.args.shared_resources.is_empty() {
             let (item, constructor) =
-                shared_resources_struct::codegen(Context::SoftwareTask(name), app);
+                shared_resources_struct::codegen(Context::SoftwareTask(name), app, analysis);
 
             root.push(item);
 
             mod_app.push(constructor);
         }
 
-        if !&task.is_extern {
-            let context = &task.context;
-            let attrs = &task.attrs;
-            let cfgs = &task.cfgs;
-            let stmts = &task.stmts;
-            let inputs = &task.inputs;
-
-            user_tasks.push(quote!(
-                #(#attrs)*
-                #(#cfgs)*
-                #[allow(non_snake_case)]
-                async fn #name<'a>(#context: #name::Context<'a> #(,#inputs)*) {
-                    use rtic::Mutex as _;
-                    use rtic::mutex::prelude::*;
-
-                    #(#stmts)*
-                }
-            ));
+        // Tasks declared in a task module are generated in that module
+        if !task.is_extern && task.module.is_none() {
+            user_tasks.push(user_task(name, task));
         }
 
         root.push(module::codegen(Context::SoftwareTask(name), app, analysis));
@@ -62,3 +51,29 @@ pub fn codegen(app: &App, analysis: &Analysis) -> TokenStream2 {
         #(#user_tasks)*
     )
 }
+
+/// Generates the user provided function of a software task
+pub fn user_task(name: &Ident, task: &SoftwareTask) -> TokenStream2 {
+    let context = &task.context;
+    let attrs = &task.attrs;
+    let cfgs = &task.cfgs;
+    let stmts = &task.stmts;
+    let inputs = &task.inputs;
+
+    let output = task.output.as_ref().map(|ty| quote!(-> #ty));
+
+    // The function is called from the `#[app]` module
+    let vis = task.module.as_ref().map(|_| quote!(pub(super)));
+
+    quote!(
+        #(#attrs)*
+        #(#cfgs)*
+        #[allow(non_snake_case)]
+        #vis async fn #name<'a>(#context: #name::Context<'a> #(,#inputs)*) #output {
+            use rtic::Mutex as _;
+            use rtic::mutex::prelude::*;
+
+            #(#stmts)*
+        }
+    )
+}
@@ -0,0 +1,67 @@
+use crate::codegen::{hardware_tasks, software_tasks};
+use crate::syntax::ast::App;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+
+/// Generates the task modules, i.e. the child modules of the `#[app]` module that declare tasks
+/// and/or resources
+pub fn codegen(app: &App) -> TokenStream2 {
+    let mut items = vec![];
+
+    for (name, module) in &app.task_modules {
+        let mut module_items = vec![];
+
+        for (task_name, task) in app
+            .software_tasks
+            .iter()
+            .filter(|(_, task)| task.module.as_ref() == Some(name))
+        {
+            let cfgs = &task.cfgs;
+
+            // The task's `Context` and `spawn` live in the `#[app]` module
+            module_items.push(quote!(
+                #(#cfgs)*
+                #[allow(unused_imports)]
+                use super::#task_name;
+            ));
+
+            if !task.is_extern {
+                module_items.push(software_tasks::user_task(task_name, task));
+            }
+        }
+
+        for (task_name, task) in app
+            .hardware_tasks
+            .iter()
+            .filter(|(_, task)| task.module.as_ref() == Some(name))
+        {
+            let cfgs = &task.cfgs;
+
+            // The task's `Context` lives in the `#[app]` module
+            module_items.push(quote!(
+                #(#cfgs)*
+                #[allow(unused_imports)]
+                use super::#task_name;
+            ));
+
+            if !task.is_extern {
+                module_items.push(hardware_tasks::user_task(task_name, task));
+            }
+        }
+
+        let attrs = &module.attrs;
+        let vis = &module.vis;
+        let user_items = &module.items;
+
+        items.push(quote!(
+            #(#attrs)*
+            #vis mod #name {
+                #(#module_items)*
+
+                #(#user_items)*
+            }
+        ));
+    }
+
+    quote!(#(#items)*)
+}
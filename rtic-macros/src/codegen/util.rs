@@ -2,7 +2,7 @@ use crate::syntax::{ast::App, Context};
 use core::sync::atomic::{AtomicUsize, Ordering};
 use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::quote;
-use syn::{Ident, PatType};
+use syn::{Attribute, Ident, LitStr, PatType};
 
 const RTIC_INTERNAL: &str = "__rtic_internal";
 
@@ -78,6 +78,17 @@ pub fn regroup_inputs(
     }
 }
 
+/// The path to the function of a task, relative to the `#[app]` module
+///
+/// Tasks declared in a task module have their function generated (or imported) in that module
+pub fn task_fn_path(name: &Ident, module: Option<&Ident>) -> TokenStream2 {
+    if let Some(module) = module {
+        quote!(#module::#name)
+    } else {
+        quote!(#name)
+    }
+}
+
 /// Get the ident for the name of the task
 pub fn get_task_name(ctxt: Context, app: &App) -> Ident {
     let s = match ctxt {
@@ -152,6 +163,14 @@ pub fn need_to_lock_ident(name: &Ident) -> Ident {
     Ident::new(&format!("{name}_that_needs_to_be_locked"), name.span())
 }
 
+pub fn raw_lock_ident(name: &Ident) -> Ident {
+    Ident::new(&format!("{name}_raw_lock"), name.span())
+}
+
+pub fn seqlock_read_ident(name: &Ident) -> Ident {
+    Ident::new(&format!("{name}_seqlock_read"), name.span())
+}
+
 pub fn zero_prio_dispatcher_ident() -> Ident {
     Ident::new("__rtic_internal_async_0_prio_dispatcher", Span::call_site())
 }
@@ -171,3 +190,46 @@ pub fn from_ptr_n_args_ident(n: usize) -> Ident {
 pub fn new_n_args_ident(n: usize) -> Ident {
     Ident::new(&format!("new_{}_args", n + 1), Span::call_site())
 }
+
+pub fn layout_n_args_ident(n: usize) -> Ident {
+    Ident::new(&format!("layout_{}_args", n + 1), Span::call_site())
+}
+
+/// The link section of the executor of a task with the `section` argument `section`, if it is
+/// not allocated on the stack of `main`
+pub fn executor_section<'a>(app: &'a App, section: &'a Option<LitStr>) -> Option<&'a LitStr> {
+    section.as_ref().or(app.args.executor_section.as_ref())
+}
+
+/// Generates the static storage of the executor of a task that is placed in the link section
+/// `section`, instead of on the stack of `main`
+pub fn executor_storage(
+    name: &Ident,
+    section: &LitStr,
+    task_fn: &TokenStream2,
+    n_inputs: usize,
+    cfgs: &[Attribute],
+) -> TokenStream2 {
+    let storage_name = internal_task_ident(name, "EXEC_STORAGE");
+    let layout_name = internal_task_ident(name, "EXEC_LAYOUT");
+    let layout_n_args = layout_n_args_ident(n_inputs);
+    let es = format!("the executor of task `{name}` is aligned to more than 8 bytes");
+
+    quote!(
+        #(#cfgs)*
+        #[allow(non_upper_case_globals)]
+        const #layout_name: ::core::alloc::Layout =
+            rtic::export::executor::AsyncTaskExecutor::#layout_n_args(&#task_fn);
+
+        #(#cfgs)*
+        const _: () = if #layout_name.align() > rtic::export::executor::STORAGE_ALIGN {
+            ::core::panic!(#es);
+        };
+
+        #(#cfgs)*
+        #[allow(non_upper_case_globals)]
+        #[link_section = #section]
+        static #storage_name: rtic::export::executor::AsyncTaskExecutorStorage<{ #layout_name.size() }> =
+            rtic::export::executor::AsyncTaskExecutorStorage::new();
+    )
+}
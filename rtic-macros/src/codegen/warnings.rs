@@ -0,0 +1,197 @@
+use crate::syntax::{analyze::Ownership, ast::App};
+use crate::{analyze::Analysis, codegen::util};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use std::collections::{BTreeSet, HashMap};
+use syn::{Expr, Ident, Member, Stmt, UnOp};
+
+/// Generates compile warnings, with `#[app(lints = true)]`, for resources and dispatchers that
+/// are unused or over-shared, and for local resources that can be task-local
+///
+/// Stable proc macros can not emit warnings, so each warning is the use of a `#[deprecated]`
+/// constant, spanned to the offending item. The warnings can be denied with `#![deny(deprecated)]`.
+pub fn codegen(app: &App, analysis: &Analysis) -> TokenStream2 {
+    if !app.args.lints {
+        return quote!();
+    }
+
+    let mut warnings = vec![];
+
+    // The tasks accessing each shared resource, and the shared resources accessed by an async
+    // task, which can not be `#[lock_free]`
+    let mut accessors: HashMap<&Ident, Vec<&Ident>> = HashMap::new();
+    let mut async_accessed = BTreeSet::new();
+    let tasks = app
+        .idle
+        .iter()
+        .map(|idle| (&idle.name, &idle.args.shared_resources, false))
+        .chain(
+            app.hardware_tasks
+                .iter()
+                .map(|(name, task)| (name, &task.args.shared_resources, task.is_async)),
+        )
+        .chain(
+            app.software_tasks
+                .iter()
+                .map(|(name, task)| (name, &task.args.shared_resources, true)),
+        );
+    for (task, shared_resources, is_async) in tasks {
+        for name in shared_resources.keys() {
+            accessors.entry(name).or_default().push(task);
+
+            if is_async {
+                async_accessed.insert(name);
+            }
+        }
+    }
+
+    for (name, res) in &app.shared_resources {
+        if !analysis.shared_resources.contains(name) {
+            warnings.push((
+                name.span(),
+                format!("unused_shared_{name}"),
+                format!("shared resource `{name}` is never accessed by a task"),
+            ));
+            continue;
+        }
+
+        let priority = match analysis.ownerships.get(name) {
+            Some(Ownership::Owned { priority } | Ownership::CoOwned { priority }) => *priority,
+            _ => continue,
+        };
+
+        match &accessors[name][..] {
+            [task] => warnings.push((
+                name.span(),
+                format!("single_task_shared_{name}"),
+                format!(
+                    "shared resource `{name}` is only accessed by task `{task}`, \
+                        it can be a `#[local]` resource"
+                ),
+            )),
+            _ if !res.properties.lock_free
+                && !res.properties.async_lock
+                && !async_accessed.contains(name) =>
+            {
+                warnings.push((
+                    name.span(),
+                    format!("single_priority_shared_{name}"),
+                    format!(
+                        "shared resource `{name}` is only accessed at priority {priority}, \
+                            it can be `#[lock_free]`"
+                    ),
+                ))
+            }
+            _ => {}
+        }
+    }
+
+    let constant_locals = constant_local_inits(app);
+    for (name, res) in &app.local_resources {
+        if !analysis.local_resources.contains(name) {
+            warnings.push((
+                name.span(),
+                format!("unused_local_{name}"),
+                format!("local resource `{name}` is never used by a task"),
+            ));
+        } else if constant_locals.contains(name) && res.attrs.is_empty() {
+            // A local resource is only used by one task, so a constant value can be given there
+            warnings.push((
+                name.span(),
+                format!("constant_local_{name}"),
+                format!(
+                    "local resource `{name}` is initialized with a constant, \
+                        it can be a task-local resource `local = [{name}: .. = ..]`"
+                ),
+            ));
+        }
+    }
+
+    let used_dispatchers = analysis
+        .interrupts
+        .values()
+        .map(|(name, _)| name)
+        .collect::<BTreeSet<_>>();
+    for name in app.args.dispatchers.keys() {
+        if !used_dispatchers.contains(name) {
+            warnings.push((
+                name.span(),
+                format!("unused_dispatcher_{name}"),
+                format!("dispatcher `{name}` is never used, no software task runs at its priority"),
+            ));
+        }
+    }
+
+    let items = warnings.into_iter().map(|(span, ident, note)| {
+        // The span of the use is where the warning points to
+        let mut ident = util::mark_internal_name(&format!("warning_{ident}"));
+        ident.set_span(span);
+
+        quote_spanned!(span=>
+            #[deprecated(note = #note)]
+            #[allow(non_upper_case_globals)]
+            #[doc(hidden)]
+            const #ident: () = ();
+
+            const _: () = #ident;
+        )
+    });
+
+    quote!(#(#items)*)
+}
+
+/// The `#[local]` resources that `#[init]` initializes with a constant expression, found in the
+/// literal of the local resources struct in the body of `#[init]`
+fn constant_local_inits(app: &App) -> BTreeSet<&Ident> {
+    fn find<'a>(expr: &'a Expr, local_struct: &Ident, found: &mut BTreeSet<&'a Ident>) {
+        match expr {
+            Expr::Struct(expr) if expr.path.is_ident(local_struct) => {
+                for field in &expr.fields {
+                    if let (Member::Named(name), true) = (&field.member, is_constant(&field.expr)) {
+                        found.insert(name);
+                    }
+                }
+            }
+            Expr::Tuple(expr) => expr
+                .elems
+                .iter()
+                .for_each(|expr| find(expr, local_struct, found)),
+            Expr::Paren(expr) => find(&expr.expr, local_struct, found),
+            Expr::Return(expr) => {
+                if let Some(expr) = &expr.expr {
+                    find(expr, local_struct, found);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn is_constant(expr: &Expr) -> bool {
+        match expr {
+            Expr::Lit(_) => true,
+            Expr::Unary(expr) => matches!(expr.op, UnOp::Neg(_)) && is_constant(&expr.expr),
+            Expr::Paren(expr) => is_constant(&expr.expr),
+            Expr::Array(expr) => expr.elems.iter().all(is_constant),
+            Expr::Tuple(expr) => expr.elems.iter().all(is_constant),
+            Expr::Repeat(expr) => is_constant(&expr.expr),
+            _ => false,
+        }
+    }
+
+    let init = &app.init;
+    let mut found = BTreeSet::new();
+
+    for stmt in &init.stmts {
+        match stmt {
+            Stmt::Local(local) => {
+                if let Some(init_expr) = &local.init {
+                    find(&init_expr.expr, &init.user_local_struct, &mut found);
+                }
+            }
+            Stmt::Expr(expr, _) => find(expr, &init.user_local_struct, &mut found),
+            _ => {}
+        }
+    }
+
+    found
+}
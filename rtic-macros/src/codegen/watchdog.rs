@@ -0,0 +1,109 @@
+use crate::codegen::util;
+use crate::syntax::ast::App;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+
+/// Generates the task watchdog, i.e. the check-in state and `check_in` of each task with a
+/// watchdog, and the monitor that feeds the hardware watchdog while all tasks are healthy
+pub fn codegen(app: &App) -> TokenStream2 {
+    let Some(monotonic) = &app.args.monotonic else {
+        return quote!();
+    };
+
+    let mut items = vec![];
+    let mut tasks = vec![];
+
+    for (name, task) in &app.software_tasks {
+        let Some(timeout) = &task.args.watchdog else {
+            continue;
+        };
+
+        let name_s = name.to_string();
+        let cfgs = &task.cfgs;
+        let watchdog_name = util::internal_task_ident(name, "WATCHDOG");
+        let internal_context_name = util::internal_task_ident(name, "Context");
+
+        items.push(quote!(
+            #(#cfgs)*
+            #[allow(non_upper_case_globals)]
+            #[doc(hidden)]
+            static #watchdog_name: rtic::watchdog::CheckIn<#monotonic> =
+                rtic::watchdog::CheckIn::new();
+
+            #(#cfgs)*
+            impl<'a> #internal_context_name<'a> {
+                /// Checks in with the watchdog, the task must check in again within its timeout
+                #[inline(always)]
+                pub fn check_in(&self) {
+                    #watchdog_name.check_in(#timeout);
+                }
+            }
+        ));
+
+        tasks.push(quote!((#name_s, &#watchdog_name)));
+    }
+
+    if tasks.is_empty() {
+        return quote!();
+    }
+
+    // Only tasks above all tasks with a watchdog can not be starved by a stuck one
+    let max_priority = app
+        .software_tasks
+        .values()
+        .filter(|task| task.args.watchdog.is_some())
+        .map(|task| task.args.priority)
+        .max()
+        .unwrap_or(0);
+    let monitors = app
+        .software_tasks
+        .iter()
+        .map(|(name, task)| (name, task.args.priority, &task.cfgs))
+        .chain(
+            app.hardware_tasks
+                .iter()
+                .filter(|(_, task)| task.is_async)
+                .map(|(name, task)| (name, task.args.priority, &task.cfgs)),
+        )
+        .filter(|(_, priority, _)| *priority > max_priority)
+        .map(|(name, _, cfgs)| {
+            let internal_context_name = util::internal_task_ident(name, "Context");
+
+            quote!(
+                #(#cfgs)*
+                impl<'a> rtic::watchdog::MonitorContext for #internal_context_name<'a> {}
+            )
+        });
+
+    let monitor_ident = util::mark_internal_name("watchdog_monitor");
+    let expired = app
+        .watchdog_expired
+        .as_ref()
+        .map_or_else(|| quote!(|_| {}), |hook| quote!(#hook));
+
+    quote!(
+        #(#items)*
+
+        #(#monitors)*
+
+        #[doc(hidden)]
+        pub async fn #monitor_ident(
+            _cx: &impl rtic::watchdog::MonitorContext,
+            period: <#monotonic as rtic::export::Monotonic>::Duration,
+            feed: impl FnMut(),
+        ) -> ! {
+            rtic::watchdog::monitor::<#monotonic>(period, &[#(#tasks,)*], feed, #expired).await
+        }
+
+        /// Task-level watchdog supervision
+        pub mod watchdog {
+            /// Calls `feed` every `period` while all tasks with a watchdog are healthy, to feed
+            /// the hardware watchdog
+            ///
+            /// Takes the context of the task it runs in, which must have a higher priority than
+            /// all tasks with a watchdog
+            #[doc(inline)]
+            pub use super::#monitor_ident as monitor;
+        }
+    )
+}
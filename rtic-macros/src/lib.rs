@@ -30,6 +30,20 @@ with_backend! {
     #[doc(hidden)]
     #[proc_macro_attribute]
     pub fn mock_app(args: TokenStream, input: TokenStream) -> TokenStream {
+        // Components are expanded by re-invoking `#[rtic::app]`, which cannot be mocked
+        match syntax::expand_component(args.clone().into(), input.clone().into()) {
+            Err(e) => return e.to_compile_error().into(),
+            Ok(Some(_)) => {
+                return syn::Error::new(
+                    proc_macro2::Span::call_site(),
+                    "components are not supported by `mock_app`",
+                )
+                .to_compile_error()
+                .into();
+            }
+            Ok(None) => {}
+        }
+
         if let Err(e) = syntax::parse(args, input) {
             e.to_compile_error().into()
         } else {
@@ -38,6 +52,18 @@ with_backend! {
     }
 }
 
+with_backend! {
+    // Used by component macros to add their items to the `#[app]` module
+    #[doc(hidden)]
+    #[proc_macro]
+    pub fn __rtic_component_callback(input: TokenStream) -> TokenStream {
+        match syntax::component_callback(input.into()) {
+            Err(e) => e.to_compile_error().into(),
+            Ok(ts) => ts.into(),
+        }
+    }
+}
+
 with_backend! {
     /// Attribute used to declare a RTIC application
     ///
@@ -48,6 +74,13 @@ with_backend! {
     /// Should never panic, cargo feeds a path which is later converted to a string
     #[proc_macro_attribute]
     pub fn app(_args: TokenStream, _input: TokenStream) -> TokenStream {
+        // Components are expanded one at a time, each expansion invokes `#[app]` again
+        match syntax::expand_component(_args.clone().into(), _input.clone().into()) {
+            Err(e) => return e.to_compile_error().into(),
+            Ok(Some(ts)) => return ts.into(),
+            Ok(None) => {}
+        }
+
         let (mut app, analysis) = match syntax::parse(_args, _input) {
             Err(e) => return e.to_compile_error().into(),
             Ok(x) => x,
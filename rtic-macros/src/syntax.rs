@@ -64,6 +64,24 @@ impl<'a> Context<'a> {
         self.is_init() || self.is_idle()
     }
 
+    /// The priority this context runs at
+    pub fn priority(&self, app: &App) -> u8 {
+        match *self {
+            Context::HardwareTask(name) => app.hardware_tasks[name].args.priority,
+            Context::Idle | Context::Init => 0,
+            Context::SoftwareTask(name) => app.software_tasks[name].args.priority,
+        }
+    }
+
+    /// Whether this context is async and can be suspended
+    pub fn is_async(&self, app: &App) -> bool {
+        match *self {
+            Context::HardwareTask(name) => app.hardware_tasks[name].is_async,
+            Context::Idle | Context::Init => false,
+            Context::SoftwareTask(_) => true,
+        }
+    }
+
     /// Whether this context has shared resources
     pub fn has_shared_resources(&self, app: &App) -> bool {
         match *self {
@@ -116,6 +134,21 @@ pub fn parse2(
     }
 }
 
+/// Expands the first `#[component]` of the `#[app]` module, if any
+///
+/// Returns `None` if the app has no (more) components
+pub fn expand_component(
+    args: TokenStream2,
+    input: TokenStream2,
+) -> Result<Option<TokenStream2>, syn::parse::Error> {
+    parse::component(args, input)
+}
+
+/// Adds the items of an expanded component to the `#[app]` module
+pub fn component_callback(input: TokenStream2) -> Result<TokenStream2, syn::parse::Error> {
+    parse::component_callback(input)
+}
+
 enum Either<A, B> {
     Left(A),
     Right(B),
@@ -31,6 +31,38 @@ impl App {
             }))
     }
 
+    /// The shared (`&`) accesses with the priority of the context and whether it is async
+    pub(crate) fn shared_resource_reads(&self) -> impl Iterator<Item = (Priority, &Ident, bool)> {
+        self.shared_resource_accesses_with_async()
+            .filter(|(_, _, access, _)| access.is_shared())
+            .map(|(priority, name, _, is_async)| (priority, name, is_async))
+    }
+
+    fn shared_resource_accesses_with_async(
+        &self,
+    ) -> impl Iterator<Item = (Priority, &Ident, Access, bool)> {
+        self.idle
+            .iter()
+            .flat_map(|idle| {
+                idle.args
+                    .shared_resources
+                    .iter()
+                    .map(move |(name, access)| (0, name, *access, false))
+            })
+            .chain(self.hardware_tasks.values().flat_map(|task| {
+                task.args
+                    .shared_resources
+                    .iter()
+                    .map(move |(name, access)| (task.args.priority, name, *access, task.is_async))
+            }))
+            .chain(self.software_tasks.values().flat_map(|task| {
+                task.args
+                    .shared_resources
+                    .iter()
+                    .map(move |(name, access)| (task.args.priority, name, *access, true))
+            }))
+    }
+
     fn is_external(task_local: &TaskLocal) -> bool {
         matches!(task_local, TaskLocal::External)
     }
@@ -78,7 +78,9 @@ pub(crate) fn app(app: &App) -> Result<Analysis, syn::Error> {
                 // Get all uses of resources annotated lock_free
                 if lf_res == r {
                     // Check so async tasks do not use lock free resources
-                    if app.software_tasks.get(task).is_some() {
+                    if app.software_tasks.get(task).is_some()
+                        || app.hardware_tasks.get(task).is_some_and(|t| t.is_async)
+                    {
                         error.push(syn::Error::new(
                             r.span(),
                             format!(
@@ -239,6 +241,27 @@ pub(crate) fn app(app: &App) -> Result<Analysis, syn::Error> {
         }
     }
 
+    // Resources that are both written (`x`) and read (`&x`) by tasks
+    let mut writers = Writers::new();
+    for (prio, name, access) in app.shared_resource_accesses() {
+        if let (Some(priority), true) = (prio, access.is_exclusive()) {
+            if app.shared_resource_reads().any(|(_, read, _)| read == name) {
+                let writer = writers.entry(name.clone()).or_insert(priority);
+                *writer = cmp::max(*writer, priority);
+            }
+        }
+    }
+
+    // A reader that could be preempted by a writer, or be suspended while holding the reference,
+    // reads the resource through a sequence lock
+    let seqlocks = app
+        .shared_resource_reads()
+        .filter(|(priority, name, is_async)| {
+            needs_seqlock(&writers, name, *priority, *is_async)
+        })
+        .map(|(_, name, _)| name.clone())
+        .collect();
+
     // Create the list of used local resource Idents
     let mut used_local_resource = IndexSet::new();
 
@@ -304,9 +327,17 @@ pub(crate) fn app(app: &App) -> Result<Analysis, syn::Error> {
         ownerships,
         send_types,
         sync_types,
+        writers,
+        seqlocks,
     })
 }
 
+fn needs_seqlock(writers: &Writers, name: &Ident, priority: Priority, is_async: bool) -> bool {
+    writers
+        .get(name)
+        .is_some_and(|&writer| is_async || writer > priority)
+}
+
 // /// Priority ceiling
 // pub type Ceiling = Option<u8>;
 
@@ -344,6 +375,23 @@ pub struct Analysis {
 
     /// These types must implement the `Sync` trait
     pub sync_types: SyncTypes,
+
+    /// Highest priority of the tasks writing the resources that are also read with `&`
+    pub writers: Writers,
+
+    /// Resources that have readers which go through a sequence lock
+    pub seqlocks: SeqLocks,
+}
+
+impl Analysis {
+    /// Whether a `&` access to `name` from a context at `priority` reads the resource through its
+    /// sequence lock instead of getting a plain `&T`
+    ///
+    /// A plain `&T` is only handed out if no writer can run while the reference is alive: the
+    /// reader can not be suspended and all writers run at the same or a lower priority.
+    pub fn seqlock_read(&self, name: &Ident, priority: Priority, is_async: bool) -> bool {
+        needs_seqlock(&self.writers, name, priority, is_async)
+    }
 }
 
 /// All channels, keyed by dispatch priority
@@ -364,6 +412,12 @@ pub type SendTypes = Set<Box<Type>>;
 /// These types must implement the `Sync` trait
 pub type SyncTypes = Set<Box<Type>>;
 
+/// Highest priority of the tasks writing a resource that is also read with `&`
+pub type Writers = IndexMap<Resource, Priority>;
+
+/// Resources read through a sequence lock
+pub type SeqLocks = IndexSet<Resource>;
+
 /// A channel used to send messages
 #[derive(Debug, Default)]
 pub struct Channel {
@@ -1,6 +1,9 @@
 //! Abstract Syntax Tree
 
-use syn::{Attribute, Expr, Ident, Item, ItemUse, Pat, PatType, Path, Stmt, Type};
+use proc_macro2::Span;
+use syn::{
+    Attribute, Expr, Ident, Item, ItemUse, LitStr, Pat, PatType, Path, Stmt, Type, Visibility,
+};
 
 use crate::syntax::{backend::BackendArgs, Map};
 
@@ -14,6 +17,9 @@ pub struct App {
     /// The name of the `const` item on which the `#[app]` attribute has been placed
     pub name: Ident,
 
+    /// Other attributes of the `#[app]` module
+    pub attrs: Vec<Attribute>,
+
     /// The `#[init]` function
     pub init: Init,
 
@@ -41,6 +47,31 @@ pub struct App {
 
     /// Async software tasks: `#[task]`
     pub software_tasks: Map<SoftwareTask>,
+
+    /// Child modules that declare tasks and/or resources
+    pub task_modules: Map<TaskModule>,
+
+    /// The `#[task_failed]` hook, called when a supervised task fails and is not restarted
+    pub task_failed: Option<Ident>,
+
+    /// The `#[watchdog_expired]` hook, called with the name of a task that missed its check-in
+    pub watchdog_expired: Option<Ident>,
+}
+
+/// A child module of the `#[app]` module that declares tasks and/or resources
+///
+/// The tasks and resources are moved into the app, the remaining items stay in the module
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct TaskModule {
+    /// Attributes that will apply to this module
+    pub attrs: Vec<Attribute>,
+
+    /// The visibility of this module
+    pub vis: Visibility,
+
+    /// User code
+    pub items: Vec<Item>,
 }
 
 /// Interrupts used to dispatch software tasks
@@ -69,6 +100,18 @@ pub struct AppArgs {
     /// Interrupts used to dispatch software tasks
     pub dispatchers: Dispatchers,
 
+    /// Generate the runtime task registry
+    pub registry: bool,
+
+    /// Warn about unused and over-shared resources and unused dispatchers
+    pub lints: bool,
+
+    /// The monotonic of the app, used by the task watchdog
+    pub monotonic: Option<Path>,
+
+    /// The link section of the executors of the async tasks without their own `section`
+    pub executor_section: Option<LitStr>,
+
     /// Backend-specific arguments
     pub backend: Option<BackendArgs>,
 }
@@ -166,6 +209,9 @@ impl Default for IdleArgs {
 pub struct SharedResourceProperties {
     /// A lock free (exclusive resource)
     pub lock_free: bool,
+
+    /// Can also be locked across `.await` points with `async_lock`
+    pub async_lock: bool,
 }
 
 /// A shared resource, defined in `#[shared]`
@@ -230,11 +276,17 @@ pub struct SoftwareTask {
     /// The inputs of this software task
     pub inputs: Vec<PatType>,
 
+    /// The return type of the task, `None` if the task returns `()`
+    pub output: Option<Box<Type>>,
+
     /// The statements that make up the task handler
     pub stmts: Vec<Stmt>,
 
     /// The task is declared externally
     pub is_extern: bool,
+
+    /// The task module this task is declared in, if any
+    pub module: Option<Ident>,
 }
 
 /// Software task metadata
@@ -249,6 +301,40 @@ pub struct SoftwareTaskArgs {
 
     /// Shared resources that can be accessed from this context
     pub shared_resources: SharedResources,
+
+    /// Generate an `extern "C"` entry point that spawns this task
+    pub export_c: bool,
+
+    /// The symbol name of the `extern "C"` entry point, `${task}_spawn` if not given
+    pub export_c_symbol: Option<LitStr>,
+
+    /// The task can be spawned through the task registry
+    pub spawn_by_id: bool,
+
+    /// When the task is re-spawned after it has completed
+    pub restart: Option<RestartPolicy>,
+
+    /// The maximum number of restarts, unlimited if not given
+    pub max_restarts: Option<u32>,
+
+    /// Function that creates the arguments of the task when it is restarted
+    pub restart_args: Option<Path>,
+
+    /// The watchdog timeout of the task, in which it must check in
+    pub watchdog: Option<Box<Expr>>,
+
+    /// The link section of the executor of this task
+    pub section: Option<LitStr>,
+}
+
+/// When a supervised software task is re-spawned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartPolicy {
+    /// Whenever the task completes
+    Always,
+
+    /// When the task returns an error
+    OnError,
 }
 
 impl Default for SoftwareTaskArgs {
@@ -257,6 +343,14 @@ impl Default for SoftwareTaskArgs {
             priority: 0,
             local_resources: LocalResources::new(),
             shared_resources: SharedResources::new(),
+            export_c: false,
+            export_c_symbol: None,
+            spawn_by_id: false,
+            restart: None,
+            max_restarts: None,
+            restart_args: None,
+            watchdog: None,
+            section: None,
         }
     }
 }
@@ -282,6 +376,12 @@ pub struct HardwareTask {
 
     /// The task is declared externally
     pub is_extern: bool,
+
+    /// The task handler is an `async` function that is polled by the interrupt handler
+    pub is_async: bool,
+
+    /// The task module this task is declared in, if any
+    pub module: Option<Ident>,
 }
 
 /// Hardware task metadata
@@ -294,11 +394,21 @@ pub struct HardwareTaskArgs {
     /// The priority of this task
     pub priority: u8,
 
+    /// The span of the `priority` argument, `None` if the default priority is used
+    #[cfg_attr(
+        not(any(feature = "riscv-slic", feature = "riscv-esp32c3")),
+        allow(dead_code)
+    )]
+    pub priority_span: Option<Span>,
+
     /// Local resources that can be accessed from this context
     pub local_resources: LocalResources,
 
     /// Shared resources that can be accessed from this context
     pub shared_resources: SharedResources,
+
+    /// The link section of the executor of this task, if it is async
+    pub section: Option<LitStr>,
 }
 
 /// A `static mut` variable local to and owned by a context
@@ -30,3 +30,9 @@ mod esp32c3;
 
 #[cfg(feature = "riscv-slic")]
 mod riscv_slic;
+
+#[cfg(any(feature = "riscv-esp32c3", feature = "riscv-slic"))]
+mod riscv_common;
+
+#[cfg(not(feature = "test-template"))]
+mod util;
@@ -1,16 +1,86 @@
 use syn::{
     parse::{Parse, ParseStream},
-    Error, Result,
+    token::Brace,
+    Error, Ident, LitBool, LitInt, Result,
 };
 
+use super::util::parse_braced_args;
+
 #[derive(Debug)]
-pub struct BackendArgs();
+pub struct BackendArgs {
+    /// Size in bytes of the MPU stack guard region at the bottom of the stack
+    pub stack_guard: Option<u32>,
+
+    /// Isolate the storage of the priority levels from each other with the MPU
+    ///
+    /// This only denies access to the storage of the other isolated levels, a level is not
+    /// restricted to the statics it uses.
+    pub isolation: bool,
+}
 
 impl Parse for BackendArgs {
     fn parse(input: ParseStream) -> Result<Self> {
-        Err(Error::new(
-            input.span(),
-            "cortex backend does not accept any arguments",
-        ))
+        if !input.peek(Brace) {
+            return Err(Error::new(
+                input.span(),
+                "cortex backend only accepts `{ stack_guard = .., isolation = .. }`",
+            ));
+        }
+
+        let mut stack_guard = None;
+        let mut isolation = false;
+
+        // The MPU code is written for PMSAv7, ARMv8-M has the incompatible PMSAv8
+        let check_pmsav7 = |ident: &Ident| {
+            if cfg!(feature = "cortex-m-pmsav7") {
+                Ok(())
+            } else {
+                Err(Error::new(
+                    ident.span(),
+                    format!("`{ident}` is only supported on ARMv6-M and ARMv7-M (PMSAv7 MPU)"),
+                ))
+            }
+        };
+
+        parse_braced_args(input, |ident, input| {
+            match &*ident.to_string() {
+                "stack_guard" => {
+                    let lit: LitInt = input.parse()?;
+
+                    // MPU regions are a power of two of at least 32 bytes
+                    match lit.base10_parse::<u32>() {
+                        Ok(size) if size >= 32 && size.is_power_of_two() => {
+                            stack_guard = Some(size)
+                        }
+                        _ => {
+                            return Err(Error::new(
+                                lit.span(),
+                                "the stack guard size must be a power of two of at least 32 bytes",
+                            ))
+                        }
+                    }
+
+                    check_pmsav7(ident)?;
+                }
+
+                "isolation" => {
+                    let lit: LitBool = input.parse()?;
+                    isolation = lit.value;
+
+                    if isolation {
+                        check_pmsav7(ident)?;
+                    }
+                }
+
+                _ => return Ok(false),
+            }
+
+            Ok(true)
+        })?;
+
+        Ok(BackendArgs {
+            stack_guard,
+            isolation,
+        })
     }
 }
@@ -1,16 +1,28 @@
 use syn::{
     parse::{Parse, ParseStream},
+    token::Brace,
     Error, Result,
 };
 
+use super::{riscv_common::StackArgs, util::parse_braced_args};
+
 #[derive(Debug)]
-pub struct BackendArgs();
+pub struct BackendArgs {
+    pub stack: StackArgs,
+}
 
 impl Parse for BackendArgs {
     fn parse(input: ParseStream) -> Result<Self> {
-        Err(Error::new(
-            input.span(),
-            "esp32c3 backend does not accept any arguments",
-        ))
+        if !input.peek(Brace) {
+            return Err(Error::new(
+                input.span(),
+                "esp32c3 backend only accepts `{ stack_paint = .., stack_guard = .. }`",
+            ));
+        }
+
+        let mut stack = StackArgs::default();
+        parse_braced_args(input, |ident, input| stack.parse_arg(ident, input))?;
+
+        Ok(BackendArgs { stack })
     }
 }
@@ -0,0 +1,49 @@
+use syn::{
+    parse::{ParseStream, Result},
+    Error, Ident, LitBool, LitInt,
+};
+
+/// Stack arguments of the RISC-V backends
+#[derive(Debug, Default)]
+pub struct StackArgs {
+    /// Paint the stack before `init` and record the high-water mark of each priority level
+    ///
+    /// The end of every interrupt handler then scans the free stack in a critical section, which
+    /// adds latency proportional to the free stack, so this is only accepted in builds with debug
+    /// assertions
+    pub paint: bool,
+
+    /// Size in bytes of the PMP guard region at the bottom of the stack
+    pub guard: Option<u32>,
+}
+
+impl StackArgs {
+    /// Parses the value of the argument `ident`, returns `false` if it is not a stack argument
+    pub fn parse_arg(&mut self, ident: &Ident, input: ParseStream) -> Result<bool> {
+        match &*ident.to_string() {
+            "stack_paint" => {
+                let lit: LitBool = input.parse()?;
+                self.paint = lit.value;
+            }
+
+            "stack_guard" => {
+                let lit: LitInt = input.parse()?;
+
+                // PMP NAPOT regions are a power of two of at least 8 bytes
+                match lit.base10_parse::<u32>() {
+                    Ok(size) if size >= 8 && size.is_power_of_two() => self.guard = Some(size),
+                    _ => {
+                        return Err(Error::new(
+                            lit.span(),
+                            "the stack guard size must be a power of two of at least 8 bytes",
+                        ))
+                    }
+                }
+            }
+
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+}
@@ -1,16 +1,43 @@
 use syn::{
     parse::{Parse, ParseStream},
-    Ident, Result,
+    token::Brace,
+    Error, Ident, Result,
 };
 
+use super::{riscv_common::StackArgs, util::parse_braced_args};
+
 #[derive(Debug)]
 pub struct BackendArgs {
     pub hart_id: Ident,
+    pub stack: StackArgs,
 }
 
 impl Parse for BackendArgs {
     fn parse(input: ParseStream) -> Result<Self> {
-        let hart_id = input.parse()?;
-        Ok(BackendArgs { hart_id })
+        // `HART0` or `{ hart_id = HART0, stack_paint = true, .. }`
+        if !input.peek(Brace) {
+            let hart_id = input.parse()?;
+            return Ok(BackendArgs {
+                hart_id,
+                stack: StackArgs::default(),
+            });
+        }
+
+        let span = input.span();
+        let mut hart_id = None;
+        let mut stack = StackArgs::default();
+
+        parse_braced_args(input, |ident, input| {
+            if ident == "hart_id" {
+                hart_id = Some(input.parse()?);
+                Ok(true)
+            } else {
+                stack.parse_arg(ident, input)
+            }
+        })?;
+
+        let hart_id = hart_id.ok_or_else(|| Error::new(span, "missing `hart_id = ...`"))?;
+
+        Ok(BackendArgs { hart_id, stack })
     }
 }
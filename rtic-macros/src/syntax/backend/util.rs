@@ -0,0 +1,43 @@
+use std::collections::HashSet;
+use syn::{
+    braced,
+    parse::{ParseStream, Result},
+    Error, Ident, Token,
+};
+
+/// Parses `{ name = value, .. }`, `arg` parses the value of each argument and returns `false` for
+/// unexpected arguments
+pub fn parse_braced_args(
+    input: ParseStream,
+    mut arg: impl FnMut(&Ident, ParseStream) -> Result<bool>,
+) -> Result<()> {
+    let content;
+    braced!(content in input);
+
+    let mut seen = HashSet::new();
+
+    loop {
+        if content.is_empty() {
+            break;
+        }
+
+        let ident: Ident = content.parse()?;
+        let _: Token![=] = content.parse()?;
+
+        if !seen.insert(ident.to_string()) {
+            return Err(Error::new(ident.span(), "argument appears more than once"));
+        }
+
+        if !arg(&ident, &content)? {
+            return Err(Error::new(ident.span(), "unexpected argument"));
+        }
+
+        if content.is_empty() {
+            break;
+        }
+
+        let _: Token![,] = content.parse()?;
+    }
+
+    Ok(())
+}
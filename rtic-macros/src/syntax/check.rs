@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use syn::parse;
+use syn::{parse, spanned::Spanned};
 
 use crate::syntax::ast::App;
 
@@ -30,22 +30,28 @@ pub fn app(app: &App) -> parse::Result<()> {
         }
     }
 
-    // Check that no resource has both types of access (`Exclusive` & `Shared`)
-    let exclusive_accesses = app
-        .shared_resource_accesses()
-        .filter_map(|(priority, name, access)| {
-            if priority.is_some() && access.is_exclusive() {
-                Some(name)
-            } else {
-                None
-            }
-        })
-        .collect::<HashSet<_>>();
+    // check that `#[async_lock]` resources are only accessed through locks, as `async_lock` gives
+    // out `&mut` access
     for (_, name, access) in app.shared_resource_accesses() {
-        if access.is_shared() && exclusive_accesses.contains(name) {
+        if access.is_shared()
+            && app
+                .shared_resources
+                .get(name)
+                .is_some_and(|res| res.properties.async_lock)
+        {
             return Err(parse::Error::new(
                 name.span(),
-                "this implementation doesn't support shared (`&-`) - exclusive (`&mut-`) locks; use `x` instead of `&x`",
+                "`#[async_lock]` resources can not be accessed with `&`",
+            ));
+        }
+    }
+
+    // check that only async hardware tasks, which have an executor, are given a `section`
+    for task in app.hardware_tasks.values() {
+        if let (false, Some(section)) = (task.is_async, &task.args.section) {
+            return Err(parse::Error::new(
+                section.span(),
+                "`section` can only be used on async tasks",
             ));
         }
     }
@@ -62,5 +68,83 @@ pub fn app(app: &App) -> parse::Result<()> {
         }
     }
 
+    // check that tasks are only spawned by ID if there is a task registry
+    if !app.args.registry {
+        if let Some(name) = app
+            .software_tasks
+            .iter()
+            .find_map(|(name, task)| task.args.spawn_by_id.then_some(name))
+        {
+            return Err(parse::Error::new(
+                name.span(),
+                "`spawn_by_id` requires the task registry; add `registry = true` to `#[app]`",
+            ));
+        }
+    }
+
+    // check that no task module collides with the `registry` module
+    if app.args.registry {
+        if let Some(name) = app
+            .software_tasks
+            .keys()
+            .chain(app.hardware_tasks.keys())
+            .find(|name| *name == "registry")
+        {
+            return Err(parse::Error::new(
+                name.span(),
+                "a task can not be named `registry` when the task registry is enabled",
+            ));
+        }
+    }
+
+    // check that the watchdog has a monotonic to measure the check-in timeouts
+    if app.args.monotonic.is_none() {
+        if let Some(name) = app
+            .software_tasks
+            .iter()
+            .find_map(|(name, task)| task.args.watchdog.as_ref().map(|_| name))
+        {
+            return Err(parse::Error::new(
+                name.span(),
+                "`watchdog` requires the app monotonic; add `monotonic = ..` to `#[app]`",
+            ));
+        }
+    }
+
+    // check that restarted tasks can get new arguments
+    for (name, task) in &app.software_tasks {
+        if task.args.restart.is_some()
+            && !task.inputs.is_empty()
+            && task.args.restart_args.is_none()
+        {
+            return Err(parse::Error::new(
+                name.span(),
+                "this task takes arguments, so restarting it requires `restart_args = ..`",
+            ));
+        }
+
+        if task.inputs.is_empty() {
+            if let Some(path) = &task.args.restart_args {
+                return Err(parse::Error::new(
+                    path.span(),
+                    "this task does not take any arguments",
+                ));
+            }
+        }
+    }
+
+    // check that the failures of supervised tasks are not dropped silently
+    if app.task_failed.is_none() {
+        if let Some(name) = app.software_tasks.iter().find_map(|(name, task)| {
+            (task.output.is_some() || task.args.restart.is_some()).then_some(name)
+        }) {
+            return Err(parse::Error::new(
+                name.span(),
+                "the failures of this task are reported to the `#[task_failed]` hook, which is \
+                 missing; add one to the app",
+            ));
+        }
+    }
+
     Ok(())
 }
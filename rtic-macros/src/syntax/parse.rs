@@ -1,4 +1,5 @@
 mod app;
+mod component;
 mod hardware_task;
 mod idle;
 mod init;
@@ -11,11 +12,14 @@ use syn::{
     braced,
     parse::{self, Parse, ParseStream, Parser},
     token::Brace,
-    Ident, Item, LitInt, Token,
+    Attribute, Expr, Ident, Item, LitInt, LitStr, Path, Token, Visibility,
 };
 
 use crate::syntax::{
-    ast::{App, AppArgs, HardwareTaskArgs, IdleArgs, InitArgs, SoftwareTaskArgs, TaskLocal},
+    ast::{
+        App, AppArgs, HardwareTaskArgs, IdleArgs, InitArgs, RestartPolicy, SoftwareTaskArgs,
+        TaskLocal,
+    },
     Either,
 };
 
@@ -27,7 +31,19 @@ pub fn app(args: TokenStream2, input: TokenStream2) -> parse::Result<App> {
     App::parse(args, input)
 }
 
+// Expand the first component of the app, if any
+pub fn component(args: TokenStream2, input: TokenStream2) -> parse::Result<Option<TokenStream2>> {
+    component::expand(args, input)
+}
+
+// Re-invoke the app with the items of an expanded component
+pub fn component_callback(input: TokenStream2) -> parse::Result<TokenStream2> {
+    component::callback(input)
+}
+
 pub(crate) struct Input {
+    pub attrs: Vec<Attribute>,
+    pub vis: Visibility,
     _mod_token: Token![mod],
     pub ident: Ident,
     _brace_token: Brace,
@@ -48,12 +64,16 @@ impl Parse for Input {
 
         let content;
 
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
         let _mod_token = input.parse()?;
         let ident = input.parse()?;
         let _brace_token = braced!(content in input);
         let items = content.call(parse_items)?;
 
         Ok(Input {
+            attrs,
+            vis,
             _mod_token,
             ident,
             _brace_token,
@@ -190,6 +210,13 @@ fn task_args(tokens: TokenStream2) -> parse::Result<Either<HardwareTaskArgs, Sof
         let mut shared_resources = None;
         let mut local_resources = None;
         let mut prio_span = None;
+        let mut export_c = None;
+        let mut spawn_by_id = None;
+        let mut restart = None;
+        let mut max_restarts = None;
+        let mut restart_args = None;
+        let mut watchdog = None;
+        let mut section = None;
 
         loop {
             if input.is_empty() {
@@ -200,8 +227,11 @@ fn task_args(tokens: TokenStream2) -> parse::Result<Either<HardwareTaskArgs, Sof
             let ident: Ident = input.parse()?;
             let ident_s = ident.to_string();
 
-            // Handle equal sign
-            let _: Token![=] = input.parse()?;
+            // Handle equal sign, flags are used without a value
+            let has_value = !matches!(&*ident_s, "export_c" | "spawn_by_id") || input.peek(Token![=]);
+            if has_value {
+                let _: Token![=] = input.parse()?;
+            }
 
             match &*ident_s {
                 "binds" => {
@@ -270,6 +300,135 @@ fn task_args(tokens: TokenStream2) -> parse::Result<Either<HardwareTaskArgs, Sof
                     local_resources = Some(util::parse_local_resources(input)?);
                 }
 
+                "export_c" => {
+                    if export_c.is_some() {
+                        return Err(parse::Error::new(
+                            ident.span(),
+                            "argument appears more than once",
+                        ));
+                    }
+
+                    // #lit, the symbol name
+                    let symbol: Option<LitStr> = if has_value {
+                        Some(input.parse()?)
+                    } else {
+                        None
+                    };
+
+                    export_c = Some((ident.span(), symbol));
+                }
+
+                "spawn_by_id" => {
+                    if spawn_by_id.is_some() {
+                        return Err(parse::Error::new(
+                            ident.span(),
+                            "argument appears more than once",
+                        ));
+                    }
+
+                    if has_value {
+                        return Err(parse::Error::new(
+                            ident.span(),
+                            "`spawn_by_id` does not take a value",
+                        ));
+                    }
+
+                    spawn_by_id = Some(ident.span());
+                }
+
+                "restart" => {
+                    if restart.is_some() {
+                        return Err(parse::Error::new(
+                            ident.span(),
+                            "argument appears more than once",
+                        ));
+                    }
+
+                    let policy: Ident = input.parse()?;
+
+                    let policy = match &*policy.to_string() {
+                        "always" => RestartPolicy::Always,
+                        "on_error" => RestartPolicy::OnError,
+                        _ => {
+                            return Err(parse::Error::new(
+                                policy.span(),
+                                "expected `always` or `on_error`",
+                            ));
+                        }
+                    };
+
+                    restart = Some((ident.span(), policy));
+                }
+
+                "max_restarts" => {
+                    if max_restarts.is_some() {
+                        return Err(parse::Error::new(
+                            ident.span(),
+                            "argument appears more than once",
+                        ));
+                    }
+
+                    // #lit
+                    let lit: LitInt = input.parse()?;
+
+                    if !lit.suffix().is_empty() {
+                        return Err(parse::Error::new(
+                            lit.span(),
+                            "this literal must be unsuffixed",
+                        ));
+                    }
+
+                    let Ok(value) = lit.base10_parse::<u32>() else {
+                        return Err(parse::Error::new(
+                            lit.span(),
+                            "this literal must be in the range 0...4294967295",
+                        ));
+                    };
+
+                    max_restarts = Some((ident.span(), value));
+                }
+
+                "restart_args" => {
+                    if restart_args.is_some() {
+                        return Err(parse::Error::new(
+                            ident.span(),
+                            "argument appears more than once",
+                        ));
+                    }
+
+                    let path: Path = input.parse()?;
+
+                    restart_args = Some((ident.span(), path));
+                }
+
+                "watchdog" => {
+                    if watchdog.is_some() {
+                        return Err(parse::Error::new(
+                            ident.span(),
+                            "argument appears more than once",
+                        ));
+                    }
+
+                    // The timeout, a duration of the app monotonic
+                    let timeout: Expr = input.parse()?;
+
+                    watchdog = Some((ident.span(), timeout));
+                }
+
+                "section" => {
+                    if section.is_some() {
+                        return Err(parse::Error::new(
+                            ident.span(),
+                            "argument appears more than once",
+                        ));
+                    }
+
+                    // #lit, the link section of the executor
+                    let lit: LitStr = input.parse()?;
+
+                    section = Some(lit);
+                }
+
                 _ => {
                     return Err(parse::Error::new(ident.span(), "unexpected argument"));
                 }
@@ -286,6 +445,38 @@ fn task_args(tokens: TokenStream2) -> parse::Result<Either<HardwareTaskArgs, Sof
         let local_resources = local_resources.unwrap_or_default();
 
         Ok(if let Some(binds) = binds {
+            if let Some((span, _)) = export_c {
+                return Err(parse::Error::new(
+                    span,
+                    "`export_c` can only be used on software tasks",
+                ));
+            }
+
+            if let Some(span) = spawn_by_id {
+                return Err(parse::Error::new(
+                    span,
+                    "`spawn_by_id` can only be used on software tasks",
+                ));
+            }
+
+            if let Some(span) = restart
+                .map(|(span, _)| span)
+                .or(max_restarts.map(|(span, _)| span))
+                .or(restart_args.as_ref().map(|(span, _)| *span))
+            {
+                return Err(parse::Error::new(
+                    span,
+                    "restart policies can only be used on software tasks",
+                ));
+            }
+
+            if let Some((span, _)) = watchdog {
+                return Err(parse::Error::new(
+                    span,
+                    "`watchdog` can only be used on software tasks",
+                ));
+            }
+
             // Hardware tasks can't run at anything lower than 1
             let priority = priority.unwrap_or(1);
 
@@ -299,17 +490,44 @@ fn task_args(tokens: TokenStream2) -> parse::Result<Either<HardwareTaskArgs, Sof
             Either::Left(HardwareTaskArgs {
                 binds,
                 priority,
+                priority_span: prio_span,
                 shared_resources,
                 local_resources,
+                section,
             })
         } else {
             // Software tasks start at idle priority
             let priority = priority.unwrap_or(0);
 
+            if restart.is_none() {
+                if let Some(span) = max_restarts
+                    .map(|(span, _)| span)
+                    .or(restart_args.as_ref().map(|(span, _)| *span))
+                {
+                    return Err(parse::Error::new(
+                        span,
+                        "this argument requires a restart policy, e.g. `restart = on_error`",
+                    ));
+                }
+            }
+
+            let (export_c, export_c_symbol) = match export_c {
+                Some((_, symbol)) => (true, symbol),
+                None => (false, None),
+            };
+
             Either::Right(SoftwareTaskArgs {
                 priority,
                 shared_resources,
                 local_resources,
+                export_c,
+                export_c_symbol,
+                spawn_by_id: spawn_by_id.is_some(),
+                restart: restart.map(|(_, policy)| policy),
+                max_restarts: max_restarts.map(|(_, value)| value),
+                restart_args: restart_args.map(|(_, path)| path),
+                watchdog: watchdog.map(|(_, timeout)| Box::new(timeout)),
+                section,
             })
         })
     })
@@ -5,13 +5,13 @@ use proc_macro2::TokenStream as TokenStream2;
 use syn::{
     parse::{self, ParseStream, Parser},
     spanned::Spanned,
-    Expr, ExprArray, Fields, ForeignItem, Ident, Item, LitBool, Path, Token, Visibility,
+    Expr, ExprArray, Fields, ForeignItem, Ident, Item, LitBool, LitStr, Meta, Path, Token, Visibility,
 };
 
 use crate::syntax::{
     ast::{
         App, AppArgs, Dispatcher, Dispatchers, HardwareTask, Idle, IdleArgs, Init, InitArgs,
-        LocalResource, SharedResource, SoftwareTask,
+        LocalResource, SharedResource, SoftwareTask, TaskModule,
     },
     backend::BackendArgs,
     parse::{self as syntax_parse, util},
@@ -28,6 +28,10 @@ impl AppArgs {
             let mut core = true;
             let mut peripherals = true;
             let mut dispatchers = Dispatchers::new();
+            let mut registry = false;
+            let mut lints = false;
+            let mut monotonic = None;
+            let mut executor_section = None;
             let mut backend = None;
 
             loop {
@@ -84,6 +88,50 @@ impl AppArgs {
                         }
                     }
 
+                    "registry" => {
+                        if let Ok(p) = input.parse::<LitBool>() {
+                            registry = p.value;
+                        } else {
+                            return Err(parse::Error::new(
+                                ident.span(),
+                                "unexpected argument value; this should be a boolean",
+                            ));
+                        }
+                    }
+
+                    "lints" => {
+                        if let Ok(p) = input.parse::<LitBool>() {
+                            lints = p.value;
+                        } else {
+                            return Err(parse::Error::new(
+                                ident.span(),
+                                "unexpected argument value; this should be a boolean",
+                            ));
+                        }
+                    }
+
+                    "monotonic" => {
+                        if let Ok(p) = input.parse::<Path>() {
+                            monotonic = Some(p);
+                        } else {
+                            return Err(parse::Error::new(
+                                ident.span(),
+                                "unexpected argument value; this should be a path",
+                            ));
+                        }
+                    }
+
+                    "executor_section" => {
+                        if let Ok(p) = input.parse::<LitStr>() {
+                            executor_section = Some(p);
+                        } else {
+                            return Err(parse::Error::new(
+                                ident.span(),
+                                "unexpected argument value; this should be a string",
+                            ));
+                        }
+                    }
+
                     "dispatchers" => {
                         if let Ok(p) = input.parse::<ExprArray>() {
                             for e in p.elems {
@@ -129,14 +177,8 @@ impl AppArgs {
                     }
 
                     "backend" => {
-                        if let Ok(p) = input.parse::<BackendArgs>() {
-                            backend = Some(p);
-                        } else {
-                            return Err(parse::Error::new(
-                                ident.span(),
-                                "unable to parse backend configuration",
-                            ));
-                        }
+                        // The backend reports what it expects
+                        backend = Some(input.parse::<BackendArgs>()?);
                     }
 
                     _ => {
@@ -163,6 +205,10 @@ impl AppArgs {
                 core,
                 peripherals,
                 dispatchers,
+                registry,
+                lints,
+                monotonic,
+                executor_section,
                 backend,
             })
         })
@@ -185,6 +231,8 @@ impl App {
 
         let mut hardware_tasks = Map::new();
         let mut software_tasks = Map::new();
+        let mut task_failed = None;
+        let mut watchdog_expired = None;
         let mut user_imports = vec![];
         let mut user_code = vec![];
 
@@ -217,7 +265,56 @@ impl App {
             Ok(())
         };
 
-        for mut item in input.items {
+        // Tasks and resources declared in child modules are handled as if they were declared in
+        // the `#[app]` module, the rest of the module is kept as is
+        let mut task_modules = Map::new();
+        let mut items = vec![];
+
+        for item in input.items {
+            match item {
+                Item::Mod(mod_)
+                    if mod_.content.as_ref().is_some_and(|(_, items)| {
+                        items.iter().any(util::is_rtic_item)
+                    }) =>
+                {
+                    let (_, mod_items) = mod_.content.expect("UNREACHABLE");
+                    let mut user_items = vec![];
+
+                    for item in mod_items {
+                        if util::is_rtic_item(&item) {
+                            items.push((Some(mod_.ident.clone()), item));
+                        } else if util::contains_rtic_items(&item) {
+                            return Err(parse::Error::new(
+                                item.span(),
+                                "tasks and resources can only be declared in the `#[app]` module or in its child modules",
+                            ));
+                        } else {
+                            user_items.push(item);
+                        }
+                    }
+
+                    task_modules.insert(
+                        mod_.ident,
+                        TaskModule {
+                            attrs: mod_.attrs,
+                            vis: mod_.vis,
+                            items: user_items,
+                        },
+                    );
+                }
+
+                item if util::contains_rtic_items(&item) => {
+                    return Err(parse::Error::new(
+                        item.span(),
+                        "tasks and resources can only be declared in the `#[app]` module or in its child modules",
+                    ));
+                }
+
+                item => items.push((None, item)),
+            }
+        }
+
+        for (module, mut item) in items {
             match item {
                 Item::Fn(mut item) => {
                     let span = item.sig.ident.span();
@@ -230,6 +327,13 @@ impl App {
                             item.attrs.remove(pos).parse_args().unwrap_or_default(),
                         )?;
 
+                        if module.is_some() {
+                            return Err(parse::Error::new(
+                                span,
+                                "`#[init]` function must be declared in the `#[app]` module",
+                            ));
+                        }
+
                         // If an init function already exists, error
                         if init.is_some() {
                             return Err(parse::Error::new(
@@ -250,6 +354,13 @@ impl App {
                             item.attrs.remove(pos).parse_args().unwrap_or_default(),
                         )?;
 
+                        if module.is_some() {
+                            return Err(parse::Error::new(
+                                span,
+                                "`#[idle]` function must be declared in the `#[app]` module",
+                            ));
+                        }
+
                         // If an idle function already exists, error
                         if idle.is_some() {
                             return Err(parse::Error::new(
@@ -282,21 +393,107 @@ impl App {
                                 check_binding(&args.binds)?;
                                 check_ident(&item.sig.ident)?;
 
-                                hardware_tasks.insert(
-                                    item.sig.ident.clone(),
-                                    HardwareTask::parse(args, item)?,
-                                );
+                                let name = item.sig.ident.clone();
+                                let mut task = HardwareTask::parse(args, item)?;
+                                task.module = module;
+
+                                hardware_tasks.insert(name, task);
                             }
 
                             Either::Right(args) => {
                                 check_ident(&item.sig.ident)?;
 
-                                software_tasks.insert(
-                                    item.sig.ident.clone(),
-                                    SoftwareTask::parse(args, item)?,
-                                );
+                                let name = item.sig.ident.clone();
+                                let mut task = SoftwareTask::parse(args, item)?;
+                                task.module = module;
+
+                                software_tasks.insert(name, task);
                             }
                         }
+                    } else if let Some(pos) = item
+                        .attrs
+                        .iter()
+                        .position(|attr| util::attr_eq(attr, "task_failed"))
+                    {
+                        if module.is_some() {
+                            return Err(parse::Error::new(
+                                span,
+                                "the `#[task_failed]` hook must be declared in the `#[app]` module",
+                            ));
+                        }
+
+                        if task_failed.is_some() {
+                            return Err(parse::Error::new(
+                                span,
+                                "the `#[task_failed]` hook must appear at most once",
+                            ));
+                        }
+
+                        if !matches!(item.attrs.remove(pos).meta, Meta::Path(_)) {
+                            return Err(parse::Error::new(
+                                span,
+                                "`#[task_failed]` does not take any arguments",
+                            ));
+                        }
+
+                        let valid_signature = util::check_fn_signature(&item, false)
+                            && item.sig.inputs.len() == 1
+                            && util::type_is_unit(&item.sig.output);
+
+                        if !valid_signature {
+                            return Err(parse::Error::new(
+                                span,
+                                "the `#[task_failed]` hook must have type signature `fn(rtic::supervisor::TaskFailure)`",
+                            ));
+                        }
+
+                        check_ident(&item.sig.ident)?;
+
+                        // The hook is an ordinary function that is called by the dispatchers
+                        task_failed = Some(item.sig.ident.clone());
+                        user_code.push(Item::Fn(item));
+                    } else if let Some(pos) = item
+                        .attrs
+                        .iter()
+                        .position(|attr| util::attr_eq(attr, "watchdog_expired"))
+                    {
+                        if module.is_some() {
+                            return Err(parse::Error::new(
+                                span,
+                                "the `#[watchdog_expired]` hook must be declared in the `#[app]` module",
+                            ));
+                        }
+
+                        if watchdog_expired.is_some() {
+                            return Err(parse::Error::new(
+                                span,
+                                "the `#[watchdog_expired]` hook must appear at most once",
+                            ));
+                        }
+
+                        if !matches!(item.attrs.remove(pos).meta, Meta::Path(_)) {
+                            return Err(parse::Error::new(
+                                span,
+                                "`#[watchdog_expired]` does not take any arguments",
+                            ));
+                        }
+
+                        let valid_signature = util::check_fn_signature(&item, false)
+                            && item.sig.inputs.len() == 1
+                            && util::type_is_unit(&item.sig.output);
+
+                        if !valid_signature {
+                            return Err(parse::Error::new(
+                                span,
+                                "the `#[watchdog_expired]` hook must have type signature `fn(&'static str)`",
+                            ));
+                        }
+
+                        check_ident(&item.sig.ident)?;
+
+                        // The hook is an ordinary function that is called by the watchdog monitor
+                        watchdog_expired = Some(item.sig.ident.clone());
+                        user_code.push(Item::Fn(item));
                     } else {
                         // Forward normal functions
                         user_code.push(Item::Fn(item.clone()));
@@ -313,17 +510,20 @@ impl App {
                     {
                         let span = struct_item.ident.span();
 
-                        shared_resources_ident = Some(struct_item.ident.clone());
+                        // Resources declared in task modules are merged into the `#[shared]`
+                        // struct of the `#[app]` module
+                        if module.is_none() {
+                            if shared_resources_ident.is_some() {
+                                return Err(parse::Error::new(
+                                    span,
+                                    "`#[shared]` struct must appear at most once",
+                                ));
+                            }
 
-                        if !shared_resources.is_empty() {
-                            return Err(parse::Error::new(
-                                span,
-                                "`#[shared]` struct must appear at most once",
-                            ));
+                            shared_resources_ident = Some(struct_item.ident.clone());
+                            shared_resources_vis = struct_item.vis.clone();
                         }
 
-                        shared_resources_vis = struct_item.vis.clone();
-
                         if let Fields::Named(fields) = &mut struct_item.fields {
                             for field in &mut fields.named {
                                 let ident = field.ident.as_ref().expect("UNREACHABLE");
@@ -351,17 +551,20 @@ impl App {
                     {
                         let span = struct_item.ident.span();
 
-                        local_resources_ident = Some(struct_item.ident.clone());
+                        // Resources declared in task modules are merged into the `#[local]`
+                        // struct of the `#[app]` module
+                        if module.is_none() {
+                            if local_resources_ident.is_some() {
+                                return Err(parse::Error::new(
+                                    span,
+                                    "`#[local]` struct must appear at most once",
+                                ));
+                            }
 
-                        if !local_resources.is_empty() {
-                            return Err(parse::Error::new(
-                                span,
-                                "`#[local]` struct must appear at most once",
-                            ));
+                            local_resources_ident = Some(struct_item.ident.clone());
+                            local_resources_vis = struct_item.vis.clone();
                         }
 
-                        local_resources_vis = struct_item.vis.clone();
-
                         if let Fields::Named(fields) = &mut struct_item.fields {
                             for field in &mut fields.named {
                                 let ident = field.ident.as_ref().expect("UNREACHABLE");
@@ -406,6 +609,14 @@ impl App {
                                 let args = InitArgs::parse(
                                     item.attrs.remove(pos).parse_args().unwrap_or_default(),
                                 )?;
+
+                                if module.is_some() {
+                                    return Err(parse::Error::new(
+                                        span,
+                                        "`#[init]` function must be declared in the `#[app]` module",
+                                    ));
+                                }
+
                                 // If an init function already exists, error
                                 if init.is_some() {
                                     return Err(parse::Error::new(
@@ -426,6 +637,13 @@ impl App {
                                     item.attrs.remove(pos).parse_args().unwrap_or_default(),
                                 )?;
 
+                                if module.is_some() {
+                                    return Err(parse::Error::new(
+                                        span,
+                                        "`#[idle]` function must be declared in the `#[app]` module",
+                                    ));
+                                }
+
                                 // If an idle function already exists, error
                                 if idle.is_some() {
                                     return Err(parse::Error::new(
@@ -465,19 +683,21 @@ impl App {
                                         check_binding(&args.binds)?;
                                         check_ident(&item.sig.ident)?;
 
-                                        hardware_tasks.insert(
-                                            item.sig.ident.clone(),
-                                            HardwareTask::parse_foreign(args, item)?,
-                                        );
+                                        let name = item.sig.ident.clone();
+                                        let mut task = HardwareTask::parse_foreign(args, item)?;
+                                        task.module = module.clone();
+
+                                        hardware_tasks.insert(name, task);
                                     }
 
                                     Either::Right(args) => {
                                         check_ident(&item.sig.ident)?;
 
-                                        software_tasks.insert(
-                                            item.sig.ident.clone(),
-                                            SoftwareTask::parse_foreign(args, item)?,
-                                        );
+                                        let name = item.sig.ident.clone();
+                                        let mut task = SoftwareTask::parse_foreign(args, item)?;
+                                        task.module = module.clone();
+
+                                        software_tasks.insert(name, task);
                                     }
                                 }
                             } else {
@@ -533,6 +753,7 @@ impl App {
         Ok(App {
             args,
             name: input.ident,
+            attrs: input.attrs,
             init,
             idle,
             shared_resources,
@@ -543,6 +764,9 @@ impl App {
             user_code,
             hardware_tasks,
             software_tasks,
+            task_modules,
+            task_failed,
+            watchdog_expired,
         })
     }
 }
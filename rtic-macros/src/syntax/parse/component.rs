@@ -0,0 +1,116 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    braced, parenthesized,
+    parse::{self, Parse, ParseStream},
+    spanned::Spanned,
+    Item, Meta,
+};
+
+use crate::syntax::parse::{util, Input};
+
+/// Expands the first `#[component]` macro invocation of the `#[app]` module
+///
+/// The component macro is invoked with a callback that re-invokes `#[app]` with the items of
+/// the component added to the `#[app]` module, so components are expanded one at a time.
+pub fn expand(args: TokenStream2, input: TokenStream2) -> parse::Result<Option<TokenStream2>> {
+    let mut input: Input = syn::parse2(input)?;
+
+    let mut component = None;
+
+    for (i, item) in input.items.iter().enumerate() {
+        let Item::Macro(mac) = item else {
+            continue;
+        };
+
+        if let Some(attr) = mac.attrs.iter().find(|attr| util::attr_eq(attr, "component")) {
+            if !matches!(attr.meta, Meta::Path(_)) {
+                return Err(parse::Error::new(
+                    attr.span(),
+                    "`#[component]` does not take any arguments",
+                ));
+            }
+
+            if let Some(ident) = &mac.ident {
+                return Err(parse::Error::new(
+                    ident.span(),
+                    "`#[component]` must be placed on a macro invocation",
+                ));
+            }
+
+            component = Some(i);
+            break;
+        }
+    }
+
+    let Some(i) = component else {
+        return Ok(None);
+    };
+
+    let Item::Macro(component) = input.items.remove(i) else {
+        unreachable!()
+    };
+
+    let path = &component.mac.path;
+    let tokens = &component.mac.tokens;
+    let attrs = &input.attrs;
+    let vis = &input.vis;
+    let ident = &input.ident;
+    let items = &input.items;
+
+    Ok(Some(quote!(
+        #path! {
+            ::rtic::export::component_callback,
+            { (#args) #(#attrs)* #vis mod #ident { #(#items)* } },
+            #tokens
+        }
+    )))
+}
+
+/// The input of the component callback: the state the callback was created with followed by the
+/// items of the component
+struct CallbackInput {
+    args: TokenStream2,
+    app: Input,
+    items: Vec<Item>,
+}
+
+impl Parse for CallbackInput {
+    fn parse(input: ParseStream<'_>) -> parse::Result<Self> {
+        let state;
+        let args;
+
+        braced!(state in input);
+        parenthesized!(args in state);
+
+        let args = args.parse()?;
+        let app = state.parse()?;
+
+        let mut items = vec![];
+        while !input.is_empty() {
+            items.push(input.parse()?);
+        }
+
+        Ok(CallbackInput { args, app, items })
+    }
+}
+
+/// Re-invokes `#[app]` with the items of an expanded component added to the `#[app]` module
+pub fn callback(input: TokenStream2) -> parse::Result<TokenStream2> {
+    let CallbackInput { args, app, items } = syn::parse2(input)?;
+
+    let attrs = &app.attrs;
+    let vis = &app.vis;
+    let ident = &app.ident;
+    let app_items = &app.items;
+
+    Ok(quote!(
+        #[::rtic::app(#args)]
+        #(#attrs)*
+        #vis mod #ident {
+            #(#app_items)*
+
+            #(#items)*
+        }
+    ))
+}
@@ -9,11 +9,12 @@ use crate::syntax::{
 impl HardwareTask {
     pub(crate) fn parse(args: HardwareTaskArgs, item: ItemFn) -> parse::Result<Self> {
         let span = item.sig.ident.span();
-        let valid_signature = util::check_fn_signature(&item, false)
+        let valid_signature = util::check_fn_signature(&item, true)
             && item.sig.inputs.len() == 1
             && util::type_is_unit(&item.sig.output);
 
         let name = item.sig.ident.to_string();
+        let is_async = item.sig.asyncness.is_some();
 
         if valid_signature {
             if let Some((context, Ok(rest))) = util::parse_inputs(item.sig.inputs, &name) {
@@ -27,6 +28,8 @@ impl HardwareTask {
                         context,
                         stmts: item.block.stmts,
                         is_extern: false,
+                        is_async,
+                        module: None,
                     });
                 }
             }
@@ -34,7 +37,9 @@ impl HardwareTask {
 
         Err(parse::Error::new(
             span,
-            format!("this task handler must have type signature `fn({name}::Context)`"),
+            format!(
+                "this task handler must have type signature `fn({name}::Context)` or `async fn({name}::Context)`"
+            ),
         ))
     }
 }
@@ -45,11 +50,12 @@ impl HardwareTask {
         item: ForeignItemFn,
     ) -> parse::Result<Self> {
         let span = item.sig.ident.span();
-        let valid_signature = util::check_foreign_fn_signature(&item, false)
+        let valid_signature = util::check_foreign_fn_signature(&item, true)
             && item.sig.inputs.len() == 1
             && util::type_is_unit(&item.sig.output);
 
         let name = item.sig.ident.to_string();
+        let is_async = item.sig.asyncness.is_some();
 
         if valid_signature {
             if let Some((context, Ok(rest))) = util::parse_inputs(item.sig.inputs, &name) {
@@ -63,6 +69,8 @@ impl HardwareTask {
                         context,
                         stmts: Vec::<Stmt>::new(),
                         is_extern: true,
+                        is_async,
+                        module: None,
                     });
                 }
             }
@@ -70,7 +78,9 @@ impl HardwareTask {
 
         Err(parse::Error::new(
             span,
-            format!("this task handler must have type signature `fn({name}::Context)`"),
+            format!(
+                "this task handler must have type signature `fn({name}::Context)` or `async fn({name}::Context)`"
+            ),
         ))
     }
 }
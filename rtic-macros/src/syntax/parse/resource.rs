@@ -1,4 +1,4 @@
-use syn::{parse, Field};
+use syn::{parse, spanned::Spanned, Field};
 
 use crate::syntax::parse::util::FilterAttrs;
 use crate::syntax::{
@@ -15,13 +15,25 @@ impl SharedResource {
         } = util::filter_attributes(item.attrs.clone());
 
         let lock_free = util::extract_lock_free(&mut attrs)?;
+        let async_lock = util::extract_async_lock(&mut attrs)?;
+        util::extract_section(&mut attrs)?;
+
+        if lock_free && async_lock {
+            return Err(parse::Error::new(
+                item.ident.as_ref().map_or(item.span(), |ident| ident.span()),
+                "a shared resource can not be both `#[lock_free]` and `#[async_lock]`",
+            ));
+        }
 
         Ok(SharedResource {
             cfgs,
             attrs,
             docs,
             ty: Box::new(item.ty.clone()),
-            properties: SharedResourceProperties { lock_free },
+            properties: SharedResourceProperties {
+                lock_free,
+                async_lock,
+            },
             vis: item.vis.clone(),
         })
     }
@@ -29,7 +41,13 @@ impl SharedResource {
 
 impl LocalResource {
     pub(crate) fn parse(item: &Field) -> parse::Result<Self> {
-        let FilterAttrs { cfgs, attrs, docs } = util::filter_attributes(item.attrs.clone());
+        let FilterAttrs {
+            cfgs,
+            mut attrs,
+            docs,
+        } = util::filter_attributes(item.attrs.clone());
+
+        util::extract_section(&mut attrs)?;
 
         Ok(LocalResource {
             cfgs,
@@ -9,7 +9,7 @@ use crate::syntax::{
 impl SoftwareTask {
     pub(crate) fn parse(args: SoftwareTaskArgs, item: ItemFn) -> parse::Result<Self> {
         let valid_signature = util::check_fn_signature(&item, true)
-            && util::type_is_unit(&item.sig.output)
+            && (util::type_is_unit(&item.sig.output) || util::type_is_result(&item.sig.output))
             && item.sig.asyncness.is_some();
 
         let span = item.sig.ident.span();
@@ -26,8 +26,10 @@ impl SoftwareTask {
                     cfgs,
                     context,
                     inputs,
+                    output: util::return_type(item.sig.output),
                     stmts: item.block.stmts,
                     is_extern: false,
+                    module: None,
                 });
             }
         }
@@ -45,7 +47,7 @@ impl SoftwareTask {
         item: ForeignItemFn,
     ) -> parse::Result<Self> {
         let valid_signature = util::check_foreign_fn_signature(&item, true)
-            && util::type_is_unit(&item.sig.output)
+            && (util::type_is_unit(&item.sig.output) || util::type_is_result(&item.sig.output))
             && item.sig.asyncness.is_some();
 
         let span = item.sig.ident.span();
@@ -62,8 +64,10 @@ impl SoftwareTask {
                     cfgs,
                     context,
                     inputs,
+                    output: util::return_type(item.sig.output),
                     stmts: Vec::<Stmt>::new(),
                     is_extern: true,
+                    module: None,
                 });
             }
         }
@@ -1,10 +1,10 @@
 use syn::{
-    bracketed,
+    bracketed, parse_quote,
     parse::{self, ParseStream},
     punctuated::Punctuated,
     spanned::Spanned,
-    Abi, AttrStyle, Attribute, Expr, ExprPath, FnArg, ForeignItemFn, Ident, ItemFn, Pat, PatType,
-    Path, PathArguments, ReturnType, Token, Type, Visibility,
+    Abi, AttrStyle, Attribute, Expr, ExprPath, FnArg, ForeignItem, ForeignItemFn, Ident, Item,
+    ItemFn, LitStr, Pat, PatType, Path, PathArguments, ReturnType, Token, Type, Visibility,
 };
 
 use crate::syntax::{
@@ -26,6 +26,45 @@ pub fn attr_eq(attr: &Attribute, name: &str) -> bool {
     }
 }
 
+/// Whether `item` is an `#[init]`, `#[idle]` or `#[task]` function, a `#[shared]` or `#[local]`
+/// struct, or an `extern` block declaring any of those functions
+pub fn is_rtic_item(item: &Item) -> bool {
+    let is_rtic_fn = |attrs: &[Attribute]| {
+        attrs.iter().any(|attr| {
+            attr_eq(attr, "init")
+                || attr_eq(attr, "idle")
+                || attr_eq(attr, "task")
+                || attr_eq(attr, "task_failed")
+                || attr_eq(attr, "watchdog_expired")
+        })
+    };
+
+    match item {
+        Item::Fn(item) => is_rtic_fn(&item.attrs),
+        Item::Struct(item) => item
+            .attrs
+            .iter()
+            .any(|attr| attr_eq(attr, "shared") || attr_eq(attr, "local")),
+        Item::ForeignMod(item) => item.items.iter().any(|item| match item {
+            ForeignItem::Fn(item) => is_rtic_fn(&item.attrs),
+            _ => false,
+        }),
+        _ => false,
+    }
+}
+
+/// Whether `item` is a module that (transitively) contains RTIC items
+pub fn contains_rtic_items(item: &Item) -> bool {
+    match item {
+        Item::Mod(item) => item.content.as_ref().is_some_and(|(_, items)| {
+            items
+                .iter()
+                .any(|item| is_rtic_item(item) || contains_rtic_items(item))
+        }),
+        _ => false,
+    }
+}
+
 /// checks that a function signature
 ///
 /// - has no bounds (like where clauses)
@@ -91,6 +130,34 @@ pub fn extract_lock_free(attrs: &mut Vec<Attribute>) -> parse::Result<bool> {
     }
 }
 
+pub fn extract_async_lock(attrs: &mut Vec<Attribute>) -> parse::Result<bool> {
+    if let Some(pos) = attrs.iter().position(|attr| attr_eq(attr, "async_lock")) {
+        attrs.remove(pos);
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+/// Turns a `#[section(".name")]` attribute into `#[link_section = ".name"]`
+pub fn extract_section(attrs: &mut Vec<Attribute>) -> parse::Result<()> {
+    if let Some(pos) = attrs.iter().position(|attr| attr_eq(attr, "section")) {
+        let attr = attrs.remove(pos);
+        let section: LitStr = attr.parse_args()?;
+
+        if attrs.iter().any(|attr| attr_eq(attr, "link_section")) {
+            return Err(parse::Error::new(
+                attr.path().span(),
+                "a resource can not have both `#[section]` and `#[link_section]`",
+            ));
+        }
+
+        attrs.push(parse_quote!(#[link_section = #section]));
+    }
+
+    Ok(())
+}
+
 pub fn parse_shared_resources(content: ParseStream<'_>) -> parse::Result<SharedResources> {
     let inner;
     bracketed!(inner in content);
@@ -374,6 +441,33 @@ pub fn type_is_path(ty: &Type, segments: &[&str]) -> bool {
     }
 }
 
+/// Whether the return type is a `Result`
+pub fn type_is_result(ty: &ReturnType) -> bool {
+    if let ReturnType::Type(_, ty) = ty {
+        if let Type::Path(ref path) = **ty {
+            path.qself.is_none()
+                && path
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "Result")
+        } else {
+            false
+        }
+    } else {
+        false
+    }
+}
+
+/// The return type, `None` if it is `()`
+pub fn return_type(ty: ReturnType) -> Option<Box<Type>> {
+    match ty {
+        _ if type_is_unit(&ty) => None,
+        ReturnType::Type(_, ty) => Some(ty),
+        ReturnType::Default => None,
+    }
+}
+
 pub fn type_is_unit(ty: &ReturnType) -> bool {
     if let ReturnType::Type(_, ty) = ty {
         if let Type::Tuple(ref tuple) = **ty {
@@ -4,4 +4,14 @@ use trybuild::TestCases;
 fn ui() {
     let t = TestCases::new();
     t.compile_fail("ui/*.rs");
+
+    // The `backend` arguments of the Cortex-M backends
+    #[cfg(any(feature = "cortex-m-source-masking", feature = "cortex-m-basepri"))]
+    t.compile_fail("ui/cortex/*.rs");
+
+    #[cfg(all(
+        any(feature = "cortex-m-source-masking", feature = "cortex-m-basepri"),
+        not(feature = "cortex-m-pmsav7")
+    ))]
+    t.compile_fail("ui/cortex-no-pmsav7/*.rs");
 }
@@ -0,0 +1,7 @@
+#![no_main]
+
+#[rtic_macros::mock_app(device = mock)]
+mod app {
+    #[component(uart)]
+    driver::component!(irq = UART0);
+}
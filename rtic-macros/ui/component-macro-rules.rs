@@ -0,0 +1,9 @@
+#![no_main]
+
+#[rtic_macros::mock_app(device = mock)]
+mod app {
+    #[component]
+    macro_rules! component {
+        () => {};
+    }
+}
@@ -0,0 +1,20 @@
+#![no_main]
+
+#[rtic_macros::mock_app(device = mock)]
+mod app {
+    #[shared]
+    struct Shared {
+        #[lock_free]
+        e1: u32,
+    }
+
+    #[local]
+    struct Local {}
+
+    #[init]
+    fn init(_: init::Context) -> (Shared, Local) {}
+
+    // e1 rejected (async task)
+    #[task(binds = UART0, priority = 1, shared = [e1])]
+    async fn uart0(cx: uart0::Context) {}
+}
@@ -0,0 +1,9 @@
+#![no_main]
+
+#[rtic_macros::mock_app(device = mock)]
+mod app {
+    #[task(binds = UART0)]
+    async fn uart0(_: uart0::Context) -> u32 {
+        0
+    }
+}
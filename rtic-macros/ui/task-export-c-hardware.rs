@@ -0,0 +1,7 @@
+#![no_main]
+
+#[rtic_macros::mock_app(device = mock)]
+mod app {
+    #[task(binds = UART0, export_c)]
+    fn uart0(_: uart0::Context) {}
+}
@@ -0,0 +1,18 @@
+#![no_main]
+
+#[rtic_macros::mock_app(device = mock, dispatchers = [EXTI0])]
+mod app {
+    #[shared]
+    struct Shared {}
+
+    #[local]
+    struct Local {}
+
+    #[init]
+    fn init(_: init::Context) -> (Shared, Local) {}
+
+    #[task(priority = 1)]
+    async fn foo(_: foo::Context) -> Result<(), ()> {
+        Err(())
+    }
+}
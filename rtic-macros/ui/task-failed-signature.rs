@@ -0,0 +1,7 @@
+#![no_main]
+
+#[rtic_macros::mock_app(device = mock)]
+mod app {
+    #[task_failed]
+    async fn task_failed(_: rtic::supervisor::TaskFailure) {}
+}
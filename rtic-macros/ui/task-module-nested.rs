@@ -0,0 +1,20 @@
+#![no_main]
+
+#[rtic_macros::mock_app(device = mock)]
+mod app {
+    #[shared]
+    struct Shared {}
+
+    #[local]
+    struct Local {}
+
+    #[init]
+    fn init(_: init::Context) -> (Shared, Local) {}
+
+    mod outer {
+        mod inner {
+            #[task]
+            async fn foo(_: foo::Context) {}
+        }
+    }
+}
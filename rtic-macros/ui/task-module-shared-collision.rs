@@ -0,0 +1,25 @@
+#![no_main]
+
+#[rtic_macros::mock_app(device = mock)]
+mod app {
+    #[shared]
+    struct Shared {
+        a: u32,
+    }
+
+    #[local]
+    struct Local {}
+
+    #[init]
+    fn init(_: init::Context) -> (Shared, Local) {}
+
+    mod foo {
+        #[shared]
+        struct Shared {
+            a: u32,
+        }
+
+        #[task(shared = [a])]
+        async fn foo(_: foo::Context) {}
+    }
+}
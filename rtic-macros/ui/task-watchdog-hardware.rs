@@ -0,0 +1,7 @@
+#![no_main]
+
+#[rtic_macros::mock_app(device = mock, monotonic = Mono)]
+mod app {
+    #[task(binds = UART0, watchdog = 100)]
+    fn uart0(_: uart0::Context) {}
+}
@@ -0,0 +1,7 @@
+#![no_main]
+
+#[rtic_macros::mock_app(device = mock, monotonic = Mono)]
+mod app {
+    #[watchdog_expired]
+    fn watchdog_expired() {}
+}
@@ -22,6 +22,23 @@
 //!     select(write_42, write_1337).await;
 //! }
 //! ```
+//!
+//! Waiters are granted access in FIFO order. To keep a high priority task from waiting behind
+//! lower priority ones, it can wait with its priority using `access_with_priority`, and is then
+//! granted access before all waiters with a lower priority. In an RTIC task, the priority is
+//! given by the context:
+//!
+//! ```rust
+//! # mod control { pub struct Context; impl Context { pub fn priority(&self) -> u8 { 3 } } }
+//! use rtic_sync::arbiter::Arbiter;
+//!
+//! static SPI: Arbiter<u32> = Arbiter::new(0);
+//!
+//! async fn control(cx: control::Context) {
+//!     // Overtakes waiting tasks with a priority lower than 3.
+//!     let spi = SPI.access_with_priority(cx.priority()).await;
+//! }
+//! ```
 
 use core::cell::UnsafeCell;
 use core::future::poll_fn;
@@ -31,16 +48,19 @@ use core::task::{Poll, Waker};
 use portable_atomic::{fence, AtomicBool, Ordering};
 
 use rtic_common::dropper::OnDrop;
-use rtic_common::wait_queue::{Link, WaitQueue};
+use rtic_common::wait_queue::{DoublyLinkedList, Link};
+use rtic_time::{Monotonic, TimeoutError};
+
+use crate::select::{select, Either};
 
 /// This is needed to make the async closure in `send` accept that we "share"
 /// the link possible between threads.
 #[derive(Clone)]
-struct LinkPtr(*mut Option<Link<Waker>>);
+struct LinkPtr(*mut Option<Link<Waiter>>);
 
 impl LinkPtr {
     /// This will dereference the pointer stored within and give out an `&mut`.
-    unsafe fn get(&mut self) -> &mut Option<Link<Waker>> {
+    unsafe fn get(&mut self) -> &mut Option<Link<Waiter>> {
         &mut *self.0
     }
 }
@@ -48,9 +68,18 @@ impl LinkPtr {
 unsafe impl Send for LinkPtr {}
 unsafe impl Sync for LinkPtr {}
 
-/// An FIFO waitqueue for use in shared bus usecases.
+/// A task waiting for access, and the priority it waits with.
+#[derive(Clone)]
+struct Waiter {
+    waker: Waker,
+    priority: u8,
+}
+
+/// An FIFO waitqueue for use in shared bus usecases, where waiters with a higher priority can
+/// overtake the others.
 pub struct Arbiter<T> {
-    wait_queue: WaitQueue,
+    // Waiters ordered by priority, and in FIFO order within a priority.
+    wait_queue: DoublyLinkedList<Waiter>,
     inner: UnsafeCell<T>,
     taken: AtomicBool,
 }
@@ -62,7 +91,7 @@ impl<T> Arbiter<T> {
     /// Create a new arbiter.
     pub const fn new(inner: T) -> Self {
         Self {
-            wait_queue: WaitQueue::new(),
+            wait_queue: DoublyLinkedList::new(),
             inner: UnsafeCell::new(inner),
             taken: AtomicBool::new(false),
         }
@@ -70,21 +99,38 @@ impl<T> Arbiter<T> {
 
     /// Get access to the inner value in the [`Arbiter`]. This will wait until access is granted,
     /// for non-blocking access use `try_access`.
+    ///
+    /// This waits with the lowest priority, after all other waiters.
     pub async fn access(&self) -> ExclusiveAccess<'_, T> {
-        let mut link_ptr: Option<Link<Waker>> = None;
+        self.access_with_priority(0).await
+    }
+
+    /// Get access to the inner value in the [`Arbiter`], waiting with `priority`. Access is granted
+    /// before all waiters with a lower priority, and after the ones with the same or a higher
+    /// priority.
+    pub async fn access_with_priority(&self, priority: u8) -> ExclusiveAccess<'_, T> {
+        let mut link_ptr: Option<Link<Waiter>> = None;
 
         // Make this future `Drop`-safe.
         // SAFETY(link_ptr): Shadow the original definition of `link_ptr` so we can't abuse it.
-        let mut link_ptr = LinkPtr(&mut link_ptr as *mut Option<Link<Waker>>);
+        let mut link_ptr = LinkPtr(&mut link_ptr as *mut Option<Link<Waiter>>);
 
         let mut link_ptr2 = link_ptr.clone();
         let dropper = OnDrop::new(|| {
-            // SAFETY: We only run this closure and dereference the pointer if we have
-            // exited the `poll_fn` below in the `drop(dropper)` call. The other dereference
-            // of this pointer is in the `poll_fn`.
-            if let Some(link) = unsafe { link_ptr2.get() } {
-                link.remove_from_list(&self.wait_queue);
-            }
+            critical_section::with(|_| {
+                // SAFETY: We only run this closure and dereference the pointer if we have
+                // exited the `poll_fn` below in the `drop(dropper)` call. The other dereference
+                // of this pointer is in the `poll_fn`.
+                if let Some(link) = unsafe { link_ptr2.get() } {
+                    if link.is_popped() {
+                        // Access has been handed over, but the future was dropped before
+                        // taking it. Pass it on, as `ExclusiveAccess` would on drop.
+                        self.release();
+                    } else {
+                        link.remove_from_list(&self.wait_queue);
+                    }
+                }
+            })
         });
 
         poll_fn(|cx| {
@@ -107,7 +153,10 @@ impl<T> Arbiter<T> {
                     }
                 } else {
                     // Place the link in the wait queue on first run.
-                    let link_ref = link.insert(Link::new(cx.waker().clone()));
+                    let link_ref = link.insert(Link::new(Waiter {
+                        waker: cx.waker().clone(),
+                        priority,
+                    }));
 
                     // SAFETY(new_unchecked): The address to the link is stable as it is defined
                     // outside this stack frame.
@@ -115,7 +164,12 @@ impl<T> Arbiter<T> {
                     // and  we make sure in `dropper` that the link is removed from the queue
                     // before dropping `link_ptr` AND `dropper` makes sure that the shadowed
                     // `link_ptr` lives until the end of the stack frame.
-                    unsafe { self.wait_queue.push(Pin::new_unchecked(link_ref)) };
+                    unsafe {
+                        self.wait_queue
+                            .push_before(Pin::new_unchecked(link_ref), |waiter| {
+                                waiter.priority < priority
+                            })
+                    };
                 }
 
                 Poll::Pending
@@ -123,8 +177,8 @@ impl<T> Arbiter<T> {
         })
         .await;
 
-        // Make sure the link is removed from the queue.
-        drop(dropper);
+        // Access has been taken, the link is not in the queue.
+        dropper.defuse();
 
         // SAFETY: One only gets here if there is exlusive access.
         ExclusiveAccess {
@@ -133,6 +187,18 @@ impl<T> Arbiter<T> {
         }
     }
 
+    /// Get access to the inner value in the [`Arbiter`], waiting at most `timeout` for access to
+    /// be granted. On timeout the place in queue is given up.
+    pub async fn access_timeout<Mono: Monotonic>(
+        &self,
+        timeout: Mono::Duration,
+    ) -> Result<ExclusiveAccess<'_, T>, TimeoutError> {
+        match select(self.access(), Mono::delay(timeout)).await {
+            Either::First(access) => Ok(access),
+            Either::Second(()) => Err(TimeoutError),
+        }
+    }
+
     /// Non-blockingly tries to access the underlying value.
     /// If someone is in queue to get it, this will return `None`.
     pub fn try_access(&self) -> Option<ExclusiveAccess<'_, T>> {
@@ -153,6 +219,21 @@ impl<T> Arbiter<T> {
             }
         })
     }
+
+    /// Releases exclusive access, handing it over to the next one in queue.
+    fn release(&self) {
+        critical_section::with(|_| {
+            fence(Ordering::SeqCst);
+
+            if self.wait_queue.is_empty() {
+                // If noone is in queue and we release exclusive access, reset `taken`.
+                self.taken.store(false, Ordering::Relaxed);
+            } else if let Some(next) = self.wait_queue.pop() {
+                // Wake the next one in queue.
+                next.waker.wake();
+            }
+        })
+    }
 }
 
 /// This token represents exclusive access to the value protected by the [`Arbiter`].
@@ -163,17 +244,7 @@ pub struct ExclusiveAccess<'a, T> {
 
 impl<'a, T> Drop for ExclusiveAccess<'a, T> {
     fn drop(&mut self) {
-        critical_section::with(|_| {
-            fence(Ordering::SeqCst);
-
-            if self.arbiter.wait_queue.is_empty() {
-                // If noone is in queue and we release exclusive access, reset `taken`.
-                self.arbiter.taken.store(false, Ordering::Relaxed);
-            } else if let Some(next) = self.arbiter.wait_queue.pop() {
-                // Wake the next one in queue.
-                next.wake();
-            }
-        })
+        self.arbiter.release();
     }
 }
 
@@ -404,4 +475,51 @@ mod tests {
 
         assert_eq!(*ARB.access().await, NUM_RUNS)
     }
+
+    #[tokio::test]
+    async fn priority_order() {
+        static ARB: Arbiter<std::vec::Vec<u8>> = Arbiter::new(std::vec::Vec::new());
+
+        let access = ARB.access().await;
+        let mut v = std::vec::Vec::new();
+
+        // Queue up in the order 1, 0, 3, 1, 2
+        for (i, priority) in [1, 0, 3, 1, 2].into_iter().enumerate() {
+            v.push(tokio::spawn(async move {
+                ARB.access_with_priority(priority).await.push(i as u8);
+            }));
+            tokio::task::yield_now().await;
+        }
+
+        drop(access);
+        for v in v {
+            v.await.unwrap();
+        }
+
+        // Highest priority first, FIFO within the same priority
+        assert_eq!(*ARB.access().await, [2, 4, 0, 3, 1]);
+    }
+
+    #[tokio::test]
+    async fn access_timeout() {
+        use crate::test_mono::TokioMono;
+        use core::time::Duration;
+
+        static ARB: Arbiter<usize> = Arbiter::new(0);
+
+        let access = ARB.access().await;
+        assert!(ARB
+            .access_timeout::<TokioMono>(Duration::from_millis(1))
+            .await
+            .is_err());
+
+        // The waiter has left the queue
+        drop(access);
+        assert!(ARB.try_access().is_some());
+
+        assert!(ARB
+            .access_timeout::<TokioMono>(Duration::from_millis(1))
+            .await
+            .is_ok());
+    }
 }
@@ -0,0 +1,421 @@
+//! A multi-producer, multi-consumer broadcast channel that can be used on no-alloc systems.
+//!
+//! Every subscriber receives every message published after it subscribed. Publishing never waits:
+//! the messages are kept in a ring buffer of `N` slots, and a subscriber that falls more than `N`
+//! messages behind misses the oldest ones and is told how many with [`Lagged`].
+//!
+//! Example usage:
+//!
+//! ```rust
+//! use rtic_sync::broadcast::Broadcast;
+//!
+//! #[derive(Clone)]
+//! enum Event {
+//!     ButtonPressed,
+//!     LinkUp,
+//! }
+//!
+//! // Up to 8 events in flight, up to 4 subscribers.
+//! static EVENTS: Broadcast<Event, 8, 4> = Broadcast::new();
+//!
+//! async fn producer() {
+//!     EVENTS.publisher().publish(Event::LinkUp);
+//! }
+//!
+//! async fn consumer() {
+//!     let mut subscriber = EVENTS.subscriber().unwrap();
+//!     loop {
+//!         match subscriber.recv().await {
+//!             Ok(event) => { /* Handle `event` */ }
+//!             Err(lagged) => { /* `lagged.0` events were missed */ }
+//!         }
+//!     }
+//! }
+//! ```
+
+use core::cell::UnsafeCell;
+use core::future::poll_fn;
+use core::task::Poll;
+
+use rtic_common::waker_registration::CriticalSectionWakerRegistration as WakerRegistration;
+
+#[cfg(feature = "defmt-03")]
+use crate::defmt;
+
+/// A broadcast channel for use in no-alloc systems. `N` sets the number of messages kept for the
+/// subscribers and `S` the maximum number of subscribers.
+///
+/// All accesses to the ring buffer are done in critical sections, `T` is cloned out of the ring
+/// buffer by the subscribers.
+pub struct Broadcast<T, const N: usize, const S: usize> {
+    // The last `N` published messages, the slots are used in turn.
+    slots: [UnsafeCell<Option<T>>; N],
+    // The number of the next message to publish, only used to count messages as it wraps at
+    // `usize::MAX` and not at a multiple of `N`.
+    tail: UnsafeCell<usize>,
+    // The slot of the next message to publish.
+    tail_slot: UnsafeCell<usize>,
+    // The subscribers that are in use.
+    taken: UnsafeCell<[bool; S]>,
+    // Wakers of the subscribers.
+    wakers: [WakerRegistration; S],
+}
+
+unsafe impl<T: Send, const N: usize, const S: usize> Send for Broadcast<T, N, S> {}
+
+unsafe impl<T: Send, const N: usize, const S: usize> Sync for Broadcast<T, N, S> {}
+
+struct UnsafeAccess<'a, T, const N: usize, const S: usize> {
+    slots: &'a [UnsafeCell<Option<T>>; N],
+    tail: &'a mut usize,
+    tail_slot: &'a mut usize,
+    taken: &'a mut [bool; S],
+}
+
+impl<T, const N: usize, const S: usize> Default for Broadcast<T, N, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize, const S: usize> Broadcast<T, N, S> {
+    /// Create a new broadcast channel.
+    pub const fn new() -> Self {
+        assert!(N > 0, "The broadcast channel needs at least 1 slot");
+
+        Self {
+            slots: [const { UnsafeCell::new(None) }; N],
+            tail: UnsafeCell::new(0),
+            tail_slot: UnsafeCell::new(0),
+            taken: UnsafeCell::new([false; S]),
+            wakers: [const { WakerRegistration::new() }; S],
+        }
+    }
+
+    /// Create a publisher of the channel.
+    pub fn publisher(&self) -> Publisher<'_, T, N, S> {
+        Publisher(self)
+    }
+
+    /// Create a subscriber of the channel, which receives the messages published from now on.
+    /// Returns `None` if there already are `S` subscribers.
+    pub fn subscriber(&self) -> Option<Subscriber<'_, T, N, S>> {
+        critical_section::with(|cs| {
+            let access = self.access(cs);
+            let idx = access.taken.iter().position(|taken| !taken)?;
+            access.taken[idx] = true;
+
+            Some(Subscriber {
+                broadcast: self,
+                idx,
+                next: *access.tail,
+            })
+        })
+    }
+
+    fn access<'a>(&'a self, _cs: critical_section::CriticalSection) -> UnsafeAccess<'a, T, N, S> {
+        // SAFETY: This is safe as are in a critical section.
+        unsafe {
+            UnsafeAccess {
+                slots: &self.slots,
+                tail: &mut *self.tail.get(),
+                tail_slot: &mut *self.tail_slot.get(),
+                taken: &mut *self.taken.get(),
+            }
+        }
+    }
+}
+
+// -------- Publisher
+
+/// A `Publisher` can publish to the channel and can be cloned.
+pub struct Publisher<'a, T, const N: usize, const S: usize>(&'a Broadcast<T, N, S>);
+
+impl<'a, T, const N: usize, const S: usize> Clone for Publisher<'a, T, N, S> {
+    fn clone(&self) -> Self {
+        Self(self.0)
+    }
+}
+
+impl<'a, T, const N: usize, const S: usize> core::fmt::Debug for Publisher<'a, T, N, S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Publisher")
+    }
+}
+
+#[cfg(feature = "defmt-03")]
+impl<'a, T, const N: usize, const S: usize> defmt::Format for Publisher<'a, T, N, S> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "Publisher",)
+    }
+}
+
+impl<'a, T, const N: usize, const S: usize> Publisher<'a, T, N, S> {
+    /// Publish a message to all subscribers. This never waits, if the ring buffer is full the
+    /// oldest message is overwritten.
+    pub fn publish(&self, val: T) {
+        let old = critical_section::with(|cs| {
+            let access = self.0.access(cs);
+            let slot = *access.tail_slot;
+            *access.tail = access.tail.wrapping_add(1);
+            *access.tail_slot = if slot + 1 == N { 0 } else { slot + 1 };
+
+            // SAFETY: This is safe as are in a critical section.
+            unsafe { (*access.slots[slot].get()).replace(val) }
+        });
+
+        // Drop the overwritten message outside of the critical section.
+        drop(old);
+
+        for waker in &self.0.wakers {
+            waker.wake();
+        }
+    }
+}
+
+// -------- Subscriber
+
+/// Error state for when a subscriber has fallen behind and messages have been overwritten before
+/// it received them. Holds the number of missed messages.
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Lagged(pub usize);
+
+/// Possible receive errors.
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TryRecvError {
+    /// Error state for when there is no new message.
+    Empty,
+    /// Error state for when messages have been missed, holds the number of missed messages.
+    Lagged(usize),
+}
+
+/// A subscriber of the channel, which receives every message published after it subscribed.
+pub struct Subscriber<'a, T, const N: usize, const S: usize> {
+    broadcast: &'a Broadcast<T, N, S>,
+    // The index of the waker of this subscriber.
+    idx: usize,
+    // The number of the next message to receive.
+    next: usize,
+}
+
+impl<'a, T, const N: usize, const S: usize> core::fmt::Debug for Subscriber<'a, T, N, S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Subscriber")
+    }
+}
+
+#[cfg(feature = "defmt-03")]
+impl<'a, T, const N: usize, const S: usize> defmt::Format for Subscriber<'a, T, N, S> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "Subscriber",)
+    }
+}
+
+impl<'a, T: Clone, const N: usize, const S: usize> Subscriber<'a, T, N, S> {
+    /// Receives the next message if there is one, non-blocking.
+    ///
+    /// If messages have been missed, `TryRecvError::Lagged` is returned once and the next call
+    /// returns the oldest message that is still available.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        critical_section::with(|cs| {
+            let access = self.broadcast.access(cs);
+            let pending = access.tail.wrapping_sub(self.next);
+
+            if pending == 0 {
+                return Err(TryRecvError::Empty);
+            }
+
+            if pending > N {
+                let missed = pending - N;
+                self.next = self.next.wrapping_add(missed);
+
+                return Err(TryRecvError::Lagged(missed));
+            }
+
+            // The message is `pending` messages before the next one to publish.
+            let slot = (*access.tail_slot + N - pending) % N;
+
+            // SAFETY: This is safe as are in a critical section. The slot holds a message as
+            // it is one of the last `N` published.
+            let val = unsafe { (*access.slots[slot].get()).clone() };
+            self.next = self.next.wrapping_add(1);
+
+            Ok(val.unwrap())
+        })
+    }
+
+    /// Receives the next message, waiting if there is none.
+    ///
+    /// If messages have been missed, `Lagged` is returned once and the next call returns the
+    /// oldest message that is still available.
+    pub async fn recv(&mut self) -> Result<T, Lagged> {
+        poll_fn(|cx| {
+            // Register waker.
+            self.broadcast.wakers[self.idx].register(cx.waker());
+
+            match self.try_recv() {
+                Ok(val) => Poll::Ready(Ok(val)),
+                Err(TryRecvError::Lagged(missed)) => Poll::Ready(Err(Lagged(missed))),
+                Err(TryRecvError::Empty) => Poll::Pending,
+            }
+        })
+        .await
+    }
+
+    /// Returns the number of messages that can be received without waiting, including the ones
+    /// that have been missed.
+    pub fn len(&self) -> usize {
+        critical_section::with(|cs| self.broadcast.access(cs).tail.wrapping_sub(self.next))
+    }
+
+    /// Is there no message to receive.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<'a, T, const N: usize, const S: usize> Drop for Subscriber<'a, T, N, S> {
+    fn drop(&mut self) {
+        // Give the subscriber back to the channel, without a stale waker for the next one
+        critical_section::with(|cs| {
+            self.broadcast.wakers[self.idx].clear();
+            self.broadcast.access(cs).taken[self.idx] = false;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::future::Future;
+
+    #[test]
+    fn subscriber_limit() {
+        let broadcast = Broadcast::<u32, 4, 2>::new();
+
+        let s1 = broadcast.subscriber().unwrap();
+        let _s2 = broadcast.subscriber().unwrap();
+        assert!(broadcast.subscriber().is_none());
+
+        drop(s1);
+        assert!(broadcast.subscriber().is_some());
+    }
+
+    #[test]
+    fn every_subscriber_receives() {
+        let broadcast = Broadcast::<u32, 4, 2>::new();
+        let p = broadcast.publisher();
+        let mut s1 = broadcast.subscriber().unwrap();
+
+        p.publish(1);
+
+        // Only messages published after subscribing are received
+        let mut s2 = broadcast.subscriber().unwrap();
+
+        p.publish(2);
+
+        assert_eq!(s1.len(), 2);
+        assert_eq!(s1.try_recv(), Ok(1));
+        assert_eq!(s1.try_recv(), Ok(2));
+        assert_eq!(s1.try_recv(), Err(TryRecvError::Empty));
+
+        assert_eq!(s2.try_recv(), Ok(2));
+        assert!(s2.is_empty());
+    }
+
+    #[test]
+    fn lagged() {
+        let broadcast = Broadcast::<u32, 4, 1>::new();
+        let p = broadcast.publisher();
+        let mut s = broadcast.subscriber().unwrap();
+
+        for i in 0..10 {
+            p.publish(i);
+        }
+
+        assert_eq!(s.try_recv(), Err(TryRecvError::Lagged(6)));
+        for i in 6..10 {
+            assert_eq!(s.try_recv(), Ok(i));
+        }
+        assert_eq!(s.try_recv(), Err(TryRecvError::Empty));
+    }
+
+    #[test]
+    fn counter_wraps() {
+        // 3 slots do not divide the wrapping message counter
+        let broadcast = Broadcast::<u32, 3, 1>::new();
+        // SAFETY: Nothing else accesses the channel.
+        unsafe { *broadcast.tail.get() = usize::MAX - 1 };
+        let p = broadcast.publisher();
+        let mut s = broadcast.subscriber().unwrap();
+
+        for i in 0..5 {
+            p.publish(i);
+        }
+
+        assert_eq!(s.try_recv(), Err(TryRecvError::Lagged(2)));
+        for i in 2..5 {
+            assert_eq!(s.try_recv(), Ok(i));
+        }
+        assert_eq!(s.try_recv(), Err(TryRecvError::Empty));
+    }
+
+    #[test]
+    fn dropped_subscriber_clears_waker() {
+        struct CountWakes(std::sync::atomic::AtomicUsize);
+
+        impl std::task::Wake for CountWakes {
+            fn wake(self: std::sync::Arc<Self>) {
+                self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            }
+        }
+
+        let broadcast = Broadcast::<u32, 4, 1>::new();
+        let wakes = std::sync::Arc::new(CountWakes(std::sync::atomic::AtomicUsize::new(0)));
+        let waker = core::task::Waker::from(wakes.clone());
+        let mut cx = core::task::Context::from_waker(&waker);
+
+        let mut s = broadcast.subscriber().unwrap();
+        {
+            let recv = core::pin::pin!(s.recv());
+            assert!(recv.poll(&mut cx).is_pending());
+        }
+        drop(s);
+
+        broadcast.publisher().publish(1);
+        assert_eq!(wakes.0.load(std::sync::atomic::Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn stress_broadcast() {
+        const NUM_SUBSCRIBERS: usize = 8;
+        const NUM_RUNS: u32 = 1_000;
+
+        static BROADCAST: Broadcast<u32, 4, NUM_SUBSCRIBERS> = Broadcast::new();
+        let mut v = std::vec::Vec::new();
+
+        for _ in 0..NUM_SUBSCRIBERS {
+            let mut s = BROADCAST.subscriber().unwrap();
+
+            v.push(tokio::spawn(async move {
+                for i in 0..NUM_RUNS {
+                    assert_eq!(s.recv().await, Ok(i));
+                }
+            }));
+        }
+
+        let p = BROADCAST.publisher();
+        for i in 0..NUM_RUNS {
+            p.publish(i);
+
+            // Let the subscribers keep up
+            tokio::task::yield_now().await;
+        }
+
+        for v in v {
+            v.await.unwrap();
+        }
+    }
+}
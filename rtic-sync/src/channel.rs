@@ -7,16 +7,20 @@ use core::{
     pin::Pin,
     ptr,
     sync::atomic::{fence, Ordering},
-    task::{Poll, Waker},
+    task::{Context, Poll, Waker},
 };
 #[doc(hidden)]
 pub use critical_section;
 use heapless::Deque;
+use portable_atomic::AtomicBool;
 use rtic_common::waker_registration::CriticalSectionWakerRegistration as WakerRegistration;
 use rtic_common::{
     dropper::OnDrop,
     wait_queue::{Link, WaitQueue},
 };
+use rtic_time::Monotonic;
+
+use crate::select::{select, Either};
 
 #[cfg(feature = "defmt-03")]
 use crate::defmt;
@@ -110,6 +114,101 @@ impl<T, const N: usize> Channel<T, N> {
     }
 }
 
+/// A channel that can be placed in a plain `static`, without `split` or [`make_channel!`].
+///
+/// The [`Receiver`] is handed out once, [`Sender`]s any number of times. As senders can always be
+/// created from the channel, the receiver never sees all senders dropped.
+///
+/// ```rust
+/// use rtic_sync::channel::StaticChannel;
+///
+/// static EVENTS: StaticChannel<u32, 4> = StaticChannel::new();
+///
+/// async fn producer() {
+///     EVENTS.sender().send(1).await.ok();
+/// }
+///
+/// async fn consumer() {
+///     let mut receiver = EVENTS.receiver().unwrap();
+///     while let Ok(event) = receiver.recv().await {
+///         // Handle `event`
+///     }
+/// }
+/// ```
+pub struct StaticChannel<T, const N: usize> {
+    channel: Channel<T, N>,
+    // The free queue has been filled.
+    initialized: AtomicBool,
+    // The receiver has been handed out.
+    receiver_taken: AtomicBool,
+}
+
+impl<T, const N: usize> Default for StaticChannel<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> StaticChannel<T, N> {
+    /// Create a new channel.
+    pub const fn new() -> Self {
+        Self {
+            channel: Channel::new(),
+            initialized: AtomicBool::new(false),
+            receiver_taken: AtomicBool::new(false),
+        }
+    }
+
+    /// Create a sender of the channel.
+    pub fn sender(&self) -> Sender<'_, T, N> {
+        critical_section::with(|cs| {
+            self.init(cs);
+
+            // Count up the reference counter
+            *self.channel.access(cs).num_senders += 1;
+        });
+
+        Sender(&self.channel)
+    }
+
+    /// Take the receiver of the channel. Returns `None` if it has already been taken.
+    pub fn receiver(&self) -> Option<Receiver<'_, T, N>> {
+        critical_section::with(|cs| {
+            if self.receiver_taken.load(Ordering::Relaxed) {
+                return None;
+            }
+
+            self.receiver_taken.store(true, Ordering::Relaxed);
+            self.init(cs);
+
+            Some(Receiver(&self.channel))
+        })
+    }
+
+    /// Fill the free queue on first use.
+    fn init(&self, cs: critical_section::CriticalSection) {
+        if self.initialized.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let access = self.channel.access(cs);
+
+        for idx in 0..N as u8 {
+            assert!(!access.freeq.is_full());
+
+            // SAFETY: This safe as the loop goes from 0 to the capacity of the underlying queue.
+            unsafe {
+                access.freeq.push_back_unchecked(idx);
+            }
+        }
+
+        // The channel itself counts as a sender, as it can create new ones.
+        *access.num_senders = 1;
+
+        self.initialized.store(true, Ordering::Relaxed);
+    }
+}
+
 /// Creates a split channel with `'static` lifetime.
 #[macro_export]
 macro_rules! make_channel {
@@ -183,6 +282,40 @@ where
     }
 }
 
+/// Errors that `send_timeout` can have.
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum SendTimeoutError<T> {
+    /// Error state for when the receiver has been dropped.
+    NoReceiver(T),
+    /// Error state for when there was no place in the queue before the timeout.
+    Timeout(T),
+}
+
+impl<T> core::fmt::Debug for SendTimeoutError<T>
+where
+    T: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SendTimeoutError::NoReceiver(v) => write!(f, "NoReceiver({v:?})"),
+            SendTimeoutError::Timeout(v) => write!(f, "Timeout({v:?})"),
+        }
+    }
+}
+
+impl<T> PartialEq for SendTimeoutError<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (SendTimeoutError::NoReceiver(v1), SendTimeoutError::NoReceiver(v2)) => v1.eq(v2),
+            (SendTimeoutError::Timeout(v1), SendTimeoutError::Timeout(v2)) => v1.eq(v2),
+            _ => false,
+        }
+    }
+}
+
 /// A `Sender` can send to the channel and can be cloned.
 pub struct Sender<'a, T, const N: usize>(&'a Channel<T, N>);
 
@@ -267,6 +400,39 @@ impl<'a, T, const N: usize> Sender<'a, T, N> {
     /// Send a value. If there is no place left in the queue this will wait until there is.
     /// If the receiver does not exist this will return an error.
     pub async fn send(&mut self, val: T) -> Result<(), NoReceiver<T>> {
+        match self.reserve().await {
+            Ok(idx) => {
+                self.send_footer(idx, val);
+
+                Ok(())
+            }
+            Err(()) => Err(NoReceiver(val)),
+        }
+    }
+
+    /// Send a value, waiting at most `timeout` for there to be place in the queue.
+    /// If the receiver does not exist or the timeout expires, the value is returned in the error.
+    pub async fn send_timeout<Mono: Monotonic>(
+        &mut self,
+        val: T,
+        timeout: Mono::Duration,
+    ) -> Result<(), SendTimeoutError<T>> {
+        // The value is not moved into the waiting, so it can be returned on timeout. The place in
+        // the queue is given up when the waiting is dropped.
+        match select(self.reserve(), Mono::delay(timeout)).await {
+            Either::First(Ok(idx)) => {
+                self.send_footer(idx, val);
+
+                Ok(())
+            }
+            Either::First(Err(())) => Err(SendTimeoutError::NoReceiver(val)),
+            Either::Second(()) => Err(SendTimeoutError::Timeout(val)),
+        }
+    }
+
+    /// Wait in line for a free slot in the queue, and take it.
+    /// If the receiver does not exist this will return an error.
+    async fn reserve(&self) -> Result<u8, ()> {
         let mut link_ptr: Option<Link<Waker>> = None;
 
         // Make this future `Drop`-safe.
@@ -275,12 +441,21 @@ impl<'a, T, const N: usize> Sender<'a, T, N> {
 
         let mut link_ptr2 = link_ptr.clone();
         let dropper = OnDrop::new(|| {
-            // SAFETY: We only run this closure and dereference the pointer if we have
-            // exited the `poll_fn` below in the `drop(dropper)` call. The other dereference
-            // of this pointer is in the `poll_fn`.
-            if let Some(link) = unsafe { link_ptr2.get() } {
-                link.remove_from_list(&self.0.wait_queue);
-            }
+            critical_section::with(|_| {
+                // SAFETY: We only run this closure and dereference the pointer if we have
+                // exited the `poll_fn` below in the `drop(dropper)` call. The other dereference
+                // of this pointer is in the `poll_fn`.
+                if let Some(link) = unsafe { link_ptr2.get() } {
+                    if link.is_popped() {
+                        // Woken for a free slot that is not taken, pass it on to the next in line.
+                        if let Some(wait_head) = self.0.wait_queue.pop() {
+                            wait_head.wake();
+                        }
+                    } else {
+                        link.remove_from_list(&self.0.wait_queue);
+                    }
+                }
+            })
         });
 
         let idx = poll_fn(|cx| {
@@ -296,25 +471,26 @@ impl<'a, T, const N: usize> Sender<'a, T, N> {
                     // SAFETY: This pointer is only dereferenced here and on drop of the future
                     // which happens outside this `poll_fn`'s stack frame.
                     let link = unsafe { link_ptr.get() };
-                    if let Some(link) = link {
-                        if !link.is_popped() {
-                            return None;
-                        } else {
+                    match link {
+                        Some(link) if !link.is_popped() => return None,
+                        Some(_) if !fq_empty => {
                             // Fall through to dequeue
                         }
-                    } else {
-                        // Place the link in the wait queue on first run.
-                        let link_ref = link.insert(Link::new(cx.waker().clone()));
-
-                        // SAFETY(new_unchecked): The address to the link is stable as it is defined
-                        // outside this stack frame.
-                        // SAFETY(push): `link_ref` lifetime comes from `link_ptr` that is shadowed,
-                        // and  we make sure in `dropper` that the link is removed from the queue
-                        // before dropping `link_ptr` AND `dropper` makes sure that the shadowed
-                        // `link_ptr` lives until the end of the stack frame.
-                        unsafe { self.0.wait_queue.push(Pin::new_unchecked(link_ref)) };
-
-                        return None;
+                        _ => {
+                            // Place the link in the wait queue on first run, or again if the
+                            // free slot it was woken for has been taken in the meantime.
+                            let link_ref = link.insert(Link::new(cx.waker().clone()));
+
+                            // SAFETY(new_unchecked): The address to the link is stable as it is
+                            // defined outside this stack frame.
+                            // SAFETY(push): `link_ref` lifetime comes from `link_ptr` that is
+                            // shadowed, and  we make sure in `dropper` that the link is removed
+                            // from the queue before dropping `link_ptr` AND `dropper` makes sure
+                            // that the shadowed `link_ptr` lives until the end of the stack frame.
+                            unsafe { self.0.wait_queue.push(Pin::new_unchecked(link_ref)) };
+
+                            return None;
+                        }
                     }
                 }
 
@@ -334,16 +510,15 @@ impl<'a, T, const N: usize> Sender<'a, T, N> {
         })
         .await;
 
-        // Make sure the link is removed from the queue.
-        drop(dropper);
-
-        if let Ok(idx) = idx {
-            self.send_footer(idx, val);
-
-            Ok(())
+        if idx.is_ok() {
+            // The slot has been taken, the link is not in the queue.
+            dropper.defuse();
         } else {
-            Err(NoReceiver(val))
+            // Make sure the link is removed from the queue.
+            drop(dropper);
         }
+
+        idx
     }
 
     /// Returns true if there is no `Receiver`s.
@@ -417,6 +592,16 @@ pub enum ReceiveError {
     Empty,
 }
 
+/// Possible `recv_timeout` errors.
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RecvTimeoutError {
+    /// Error state for when all senders has been dropped.
+    NoSender,
+    /// Error state for when no value was received before the timeout.
+    Timeout,
+}
+
 impl<'a, T, const N: usize> Receiver<'a, T, N> {
     /// Receives a value if there is one in the channel, non-blocking.
     pub fn try_recv(&mut self) -> Result<T, ReceiveError> {
@@ -452,25 +637,39 @@ impl<'a, T, const N: usize> Receiver<'a, T, N> {
     /// If all senders are dropped this will error with `NoSender`.
     pub async fn recv(&mut self) -> Result<T, ReceiveError> {
         // There was nothing in the queue, setup the waiting.
-        poll_fn(|cx| {
-            // Register waker.
-            // TODO: Should it happen here or after the if? This might cause a spurious wake.
-            self.0.receiver_waker.register(cx.waker());
-
-            // Try to dequeue.
-            match self.try_recv() {
-                Ok(val) => {
-                    return Poll::Ready(Ok(val));
-                }
-                Err(ReceiveError::NoSender) => {
-                    return Poll::Ready(Err(ReceiveError::NoSender));
-                }
-                _ => {}
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Receives a value, waiting at most `timeout` if the queue is empty.
+    /// If all senders are dropped this will error with `NoSender`.
+    pub async fn recv_timeout<Mono: Monotonic>(
+        &mut self,
+        timeout: Mono::Duration,
+    ) -> Result<T, RecvTimeoutError> {
+        match select(self.recv(), Mono::delay(timeout)).await {
+            Either::First(Ok(val)) => Ok(val),
+            Either::First(Err(_)) => Err(RecvTimeoutError::NoSender),
+            Either::Second(()) => Err(RecvTimeoutError::Timeout),
+        }
+    }
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, ReceiveError>> {
+        // Register waker.
+        // TODO: Should it happen here or after the if? This might cause a spurious wake.
+        self.0.receiver_waker.register(cx.waker());
+
+        // Try to dequeue.
+        match self.try_recv() {
+            Ok(val) => {
+                return Poll::Ready(Ok(val));
+            }
+            Err(ReceiveError::NoSender) => {
+                return Poll::Ready(Err(ReceiveError::NoSender));
             }
+            _ => {}
+        }
 
-            Poll::Pending
-        })
-        .await
+        Poll::Pending
     }
 
     /// Returns true if there are no `Sender`s.
@@ -500,6 +699,200 @@ impl<'a, T, const N: usize> Drop for Receiver<'a, T, N> {
     }
 }
 
+#[cfg(feature = "futures-03")]
+impl<'a, T, const N: usize> futures_core::Stream for Receiver<'a, T, N> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        // The stream ends when all senders are dropped and the queue is empty.
+        self.get_mut().poll_recv(cx).map(Result::ok)
+    }
+}
+
+#[cfg(feature = "futures-03")]
+impl<'a, T, const N: usize> futures_core::FusedStream for Receiver<'a, T, N> {
+    fn is_terminated(&self) -> bool {
+        self.is_closed() && self.is_empty()
+    }
+}
+
+// -------- Sink
+
+#[cfg(feature = "futures-03")]
+impl<'a, T, const N: usize> Sender<'a, T, N> {
+    /// Turn the sender into a [`futures_sink::Sink`].
+    ///
+    /// The sink waits in the same queue as [`Sender::send`], and needs to be pinned as it holds
+    /// its place in the queue.
+    pub fn into_sink(self) -> SenderSink<'a, T, N> {
+        SenderSink {
+            sender: self,
+            link: None,
+            reserved: None,
+        }
+    }
+}
+
+/// A [`Sender`] that implements [`futures_sink::Sink`], created with [`Sender::into_sink`].
+///
+/// `poll_ready` reserves a slot in the queue, which is filled by `start_send`.
+#[cfg(feature = "futures-03")]
+pub struct SenderSink<'a, T, const N: usize> {
+    sender: Sender<'a, T, N>,
+    // The place of the sink in the wait queue, the link is pinned with the sink.
+    link: Option<Link<Waker>>,
+    // The slot reserved by `poll_ready`.
+    reserved: Option<u8>,
+}
+
+#[cfg(feature = "futures-03")]
+impl<'a, T, const N: usize> core::fmt::Debug for SenderSink<'a, T, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "SenderSink")
+    }
+}
+
+#[cfg(all(feature = "futures-03", feature = "defmt-03"))]
+impl<'a, T, const N: usize> defmt::Format for SenderSink<'a, T, N> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "SenderSink",)
+    }
+}
+
+#[cfg(feature = "futures-03")]
+impl<'a, T, const N: usize> SenderSink<'a, T, N> {
+    /// Give a reserved slot back to the queue, and wake the next sender in line for it.
+    fn release(&mut self) {
+        if let Some(idx) = self.reserved.take() {
+            let channel = self.sender.0;
+
+            critical_section::with(|cs| {
+                assert!(!channel.access(cs).freeq.is_full());
+                unsafe { channel.access(cs).freeq.push_back_unchecked(idx) }
+            });
+
+            if let Some(wait_head) = channel.wait_queue.pop() {
+                wait_head.wake();
+            }
+        }
+    }
+}
+
+#[cfg(feature = "futures-03")]
+impl<'a, T, const N: usize> futures_sink::Sink<T> for SenderSink<'a, T, N> {
+    type Error = NoReceiver<()>;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // SAFETY: The link is never moved out of the pinned sink.
+        let this = unsafe { self.get_unchecked_mut() };
+
+        if this.reserved.is_some() {
+            return Poll::Ready(Ok(()));
+        }
+
+        if this.sender.is_closed() {
+            return Poll::Ready(Err(NoReceiver(())));
+        }
+
+        let channel = this.sender.0;
+        let link = &mut this.link;
+
+        //  Do all this in one critical section, else there can be race conditions
+        let reserved = critical_section::with(|cs| {
+            // First in line when the link has been popped, or when noone is waiting.
+            let first = match link {
+                Some(link) => link.is_popped(),
+                None => channel.wait_queue.is_empty(),
+            };
+
+            if first {
+                if let Some(idx) = channel.access(cs).freeq.pop_front() {
+                    // The link is not in the queue, it is safe to drop.
+                    *link = None;
+
+                    return Some(idx);
+                }
+            }
+
+            if link.as_ref().is_none_or(|link| link.is_popped()) {
+                // Place the link in the wait queue on first run, or again if the slot it was
+                // woken for has been taken.
+                let link_ref = link.insert(Link::new(cx.waker().clone()));
+
+                // SAFETY(new_unchecked): The address to the link is stable as the sink is
+                // pinned.
+                // SAFETY(push): The link is removed from the queue when the sink is dropped.
+                unsafe { channel.wait_queue.push(Pin::new_unchecked(link_ref)) };
+            }
+
+            None
+        });
+
+        match reserved {
+            Some(idx) => {
+                this.reserved = Some(idx);
+
+                Poll::Ready(Ok(()))
+            }
+            None => Poll::Pending,
+        }
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        // SAFETY: The link is never moved out of the pinned sink.
+        let this = unsafe { self.get_unchecked_mut() };
+
+        if this.sender.is_closed() {
+            this.release();
+
+            return Err(NoReceiver(()));
+        }
+
+        let idx = this
+            .reserved
+            .take()
+            .expect("`poll_ready` must be ready before calling `start_send`");
+
+        this.sender.send_footer(idx, item);
+
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // Sent values are in the queue, there is nothing to flush.
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // SAFETY: The link is never moved out of the pinned sink.
+        unsafe { self.get_unchecked_mut() }.release();
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "futures-03")]
+impl<'a, T, const N: usize> Drop for SenderSink<'a, T, N> {
+    fn drop(&mut self) {
+        self.release();
+
+        // Like the dropper of `Sender::reserve`, so that the link can not be popped between the
+        // check and the removal.
+        critical_section::with(|_| {
+            if let Some(link) = &self.link {
+                if link.is_popped() {
+                    // Woken for a free slot that is not taken, pass it on to the next in line.
+                    if let Some(wait_head) = self.sender.0.wait_queue.pop() {
+                        wait_head.wake();
+                    }
+                } else {
+                    link.remove_from_list(&self.sender.0.wait_queue);
+                }
+            }
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -612,6 +1005,171 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "futures-03")]
+    #[tokio::test]
+    async fn stream() {
+        use futures_util::StreamExt;
+
+        let (mut s, r) = make_channel!(u32, 10);
+
+        for i in 0..5 {
+            s.try_send(i).unwrap();
+        }
+        drop(s);
+
+        // The stream ends when all senders are dropped
+        let even: std::vec::Vec<_> = r
+            .filter(|v| core::future::ready(v % 2 == 0))
+            .collect()
+            .await;
+        assert_eq!(even, [0, 2, 4]);
+    }
+
+    #[cfg(feature = "futures-03")]
+    #[tokio::test]
+    async fn sink() {
+        use futures_util::StreamExt;
+
+        const NUM_RUNS: u32 = 100;
+
+        let (s, r) = make_channel!(u32, 3);
+        let mut other = s.clone();
+
+        let forward = tokio::spawn(async move {
+            let sink = core::pin::pin!(s.into_sink());
+            futures_util::stream::iter(0..NUM_RUNS)
+                .map(Ok)
+                .forward(sink)
+                .await
+                .unwrap();
+        });
+
+        // A plain sender waits in the same queue as the sink
+        let send = tokio::spawn(async move {
+            for i in NUM_RUNS..2 * NUM_RUNS {
+                other.send(i).await.unwrap();
+            }
+        });
+
+        let mut received: std::vec::Vec<_> = r.collect().await;
+        received.sort();
+        assert!(received.into_iter().eq(0..2 * NUM_RUNS));
+
+        forward.await.unwrap();
+        send.await.unwrap();
+    }
+
+    #[cfg(feature = "futures-03")]
+    #[tokio::test]
+    async fn sink_closed() {
+        use futures_util::SinkExt;
+
+        let (s, r) = make_channel!(u32, 3);
+        let mut sink = core::pin::pin!(s.into_sink());
+
+        sink.send(1).await.unwrap();
+        drop(r);
+        assert!(sink.send(2).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn send_timeout() {
+        use crate::test_mono::TokioMono;
+        use core::time::Duration;
+
+        let (mut s, mut r) = make_channel!(u32, 1);
+        s.try_send(1).unwrap();
+
+        // The value is given back on timeout
+        assert_eq!(
+            s.send_timeout::<TokioMono>(2, Duration::from_millis(1))
+                .await,
+            Err(SendTimeoutError::Timeout(2))
+        );
+
+        // The sender has left the queue
+        assert_eq!(r.try_recv(), Ok(1));
+        s.try_send(3).unwrap();
+
+        drop(r);
+        assert_eq!(
+            s.send_timeout::<TokioMono>(4, Duration::from_millis(1))
+                .await,
+            Err(SendTimeoutError::NoReceiver(4))
+        );
+    }
+
+    #[tokio::test]
+    async fn recv_timeout() {
+        use crate::test_mono::TokioMono;
+        use core::time::Duration;
+
+        let (mut s, mut r) = make_channel!(u32, 1);
+
+        assert_eq!(
+            r.recv_timeout::<TokioMono>(Duration::from_millis(1)).await,
+            Err(RecvTimeoutError::Timeout)
+        );
+
+        s.try_send(1).unwrap();
+        assert_eq!(
+            r.recv_timeout::<TokioMono>(Duration::from_millis(1)).await,
+            Ok(1)
+        );
+
+        drop(s);
+        assert_eq!(
+            r.recv_timeout::<TokioMono>(Duration::from_millis(1)).await,
+            Err(RecvTimeoutError::NoSender)
+        );
+    }
+
+    #[test]
+    fn static_channel_receiver_once() {
+        static CHANNEL: StaticChannel<u32, 4> = StaticChannel::new();
+
+        let mut r = CHANNEL.receiver().unwrap();
+        assert!(CHANNEL.receiver().is_none());
+
+        // Senders can come and go without closing the channel
+        CHANNEL.sender().try_send(1).unwrap();
+        assert!(!r.is_closed());
+        assert_eq!(r.try_recv(), Ok(1));
+        assert_eq!(r.try_recv(), Err(ReceiveError::Empty));
+
+        drop(r);
+        assert!(CHANNEL.sender().is_closed());
+        assert!(CHANNEL.receiver().is_none());
+    }
+
+    #[tokio::test]
+    async fn stress_static_channel() {
+        const NUM_RUNS: usize = 1_000;
+        const QUEUE_SIZE: usize = 10;
+
+        static CHANNEL: StaticChannel<u32, QUEUE_SIZE> = StaticChannel::new();
+        let mut v = std::vec::Vec::new();
+
+        for i in 0..NUM_RUNS {
+            v.push(tokio::spawn(async move {
+                CHANNEL.sender().send(i as _).await.unwrap();
+            }));
+        }
+
+        let mut r = CHANNEL.receiver().unwrap();
+        let mut map = std::collections::BTreeSet::new();
+
+        for _ in 0..NUM_RUNS {
+            map.insert(r.recv().await.unwrap());
+        }
+
+        assert_eq!(map.len(), NUM_RUNS);
+
+        for v in v {
+            v.await.unwrap();
+        }
+    }
+
     fn make() {
         let _ = make_channel!(u32, 10);
     }
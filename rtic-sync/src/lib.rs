@@ -7,9 +7,18 @@
 use defmt_03 as defmt;
 
 pub mod arbiter;
+pub mod broadcast;
 pub mod channel;
+pub mod oneshot;
+pub mod pipe;
+pub mod select;
+pub mod semaphore;
+pub mod watch;
 pub use portable_atomic;
 
 #[cfg(test)]
 #[macro_use]
 extern crate std;
+
+#[cfg(test)]
+mod test_mono;
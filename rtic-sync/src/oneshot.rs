@@ -0,0 +1,367 @@
+//! A oneshot channel, for sending a single value from one task to another, that can be used on
+//! no-alloc systems.
+//!
+//! The typical use is the reply to a request: the requester sends the [`Sender`] along with the
+//! request, and awaits the [`Receiver`] for the reply. The same [`Oneshot`] can be split again for
+//! the next request once both halves of the previous one have been dropped.
+//!
+//! Example usage:
+//!
+//! ```rust
+//! use rtic_sync::channel::Sender as RequestSender;
+//! use rtic_sync::oneshot::{Oneshot, Sender};
+//!
+//! static REPLY: Oneshot<u32> = Oneshot::new();
+//!
+//! struct Request {
+//!     address: u8,
+//!     reply: Sender<'static, u32>,
+//! }
+//!
+//! async fn requester(requests: &mut RequestSender<'static, Request, 4>) {
+//!     // The previous request is done, so the channel is free
+//!     let (reply, value) = REPLY.split().unwrap();
+//!     requests.send(Request { address: 0x10, reply }).await.ok();
+//!
+//!     match value.await {
+//!         Ok(value) => { /* Use `value` */ }
+//!         Err(_) => { /* The request was dropped without a reply */ }
+//!     }
+//! }
+//!
+//! fn server(request: Request) {
+//!     request.reply.send(42).ok();
+//! }
+//! ```
+
+use core::cell::UnsafeCell;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use portable_atomic::{AtomicBool, Ordering};
+
+use rtic_common::waker_registration::CriticalSectionWakerRegistration as WakerRegistration;
+
+#[cfg(feature = "defmt-03")]
+use crate::defmt;
+
+/// The storage of a oneshot channel.
+///
+/// The value is moved in and out of the storage in critical sections.
+pub struct Oneshot<T> {
+    // The value, once it has been sent.
+    value: UnsafeCell<Option<T>>,
+    // Waker for the receiver.
+    receiver_waker: WakerRegistration,
+    // Keep track of the sender.
+    sender_dropped: UnsafeCell<bool>,
+    // Keep track of the receiver.
+    receiver_dropped: UnsafeCell<bool>,
+    // The channel has been split, and not both halves have been dropped yet.
+    in_use: AtomicBool,
+}
+
+unsafe impl<T: Send> Send for Oneshot<T> {}
+
+unsafe impl<T: Send> Sync for Oneshot<T> {}
+
+struct UnsafeAccess<'a, T> {
+    value: &'a mut Option<T>,
+    sender_dropped: &'a mut bool,
+    receiver_dropped: &'a mut bool,
+}
+
+impl<T> Default for Oneshot<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Oneshot<T> {
+    /// Create a new oneshot channel.
+    pub const fn new() -> Self {
+        Self {
+            value: UnsafeCell::new(None),
+            receiver_waker: WakerRegistration::new(),
+            sender_dropped: UnsafeCell::new(false),
+            receiver_dropped: UnsafeCell::new(false),
+            in_use: AtomicBool::new(false),
+        }
+    }
+
+    /// Split the oneshot channel into a `Sender`/`Receiver` pair.
+    ///
+    /// Returns `None` if the channel is still in use, it can be split again once both halves of
+    /// the previous split have been dropped.
+    pub fn split(&self) -> Option<(Sender<'_, T>, Receiver<'_, T>)> {
+        critical_section::with(|cs| {
+            if self.in_use.load(Ordering::Relaxed) {
+                return None;
+            }
+
+            self.in_use.store(true, Ordering::Relaxed);
+
+            let access = self.access(cs);
+            *access.value = None;
+            *access.sender_dropped = false;
+            *access.receiver_dropped = false;
+
+            Some((Sender(self), Receiver(self)))
+        })
+    }
+
+    fn access<'a>(&'a self, _cs: critical_section::CriticalSection) -> UnsafeAccess<'a, T> {
+        // SAFETY: This is safe as are in a critical section.
+        unsafe {
+            UnsafeAccess {
+                value: &mut *self.value.get(),
+                sender_dropped: &mut *self.sender_dropped.get(),
+                receiver_dropped: &mut *self.receiver_dropped.get(),
+            }
+        }
+    }
+}
+
+// -------- Sender
+
+/// The sending half of a oneshot channel, which is consumed by sending.
+pub struct Sender<'a, T>(&'a Oneshot<T>);
+
+unsafe impl<'a, T: Send> Send for Sender<'a, T> {}
+
+impl<'a, T> core::fmt::Debug for Sender<'a, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Sender")
+    }
+}
+
+#[cfg(feature = "defmt-03")]
+impl<'a, T> defmt::Format for Sender<'a, T> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "Sender",)
+    }
+}
+
+impl<'a, T> Sender<'a, T> {
+    /// Send the value. If the receiver has been dropped, the value is returned as an error.
+    pub fn send(self, val: T) -> Result<(), T> {
+        critical_section::with(|cs| {
+            let access = self.0.access(cs);
+
+            if *access.receiver_dropped {
+                return Err(val);
+            }
+
+            *access.value = Some(val);
+
+            Ok(())
+        })
+        // The receiver is woken when `self` is dropped.
+    }
+
+    /// Returns true if the receiver has been dropped.
+    pub fn is_canceled(&self) -> bool {
+        critical_section::with(|cs| *self.0.access(cs).receiver_dropped)
+    }
+}
+
+impl<'a, T> Drop for Sender<'a, T> {
+    fn drop(&mut self) {
+        critical_section::with(|cs| {
+            let access = self.0.access(cs);
+            *access.sender_dropped = true;
+
+            // Free the channel if this was the last half.
+            if *access.receiver_dropped {
+                self.0.in_use.store(false, Ordering::Relaxed);
+            }
+        });
+
+        // Wake the receiver, to take the value or to do error handling.
+        self.0.receiver_waker.wake();
+    }
+}
+
+// -------- Receiver
+
+/// Error state for when the sender has been dropped without sending a value.
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Canceled;
+
+/// Possible receive errors.
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TryRecvError {
+    /// Error state for when the sender has been dropped without sending a value.
+    Canceled,
+    /// Error state for when the value has not been sent yet.
+    Empty,
+}
+
+/// The receiving half of a oneshot channel, which is a future of the value.
+///
+/// Once the value has been received, the receiver returns `Canceled`.
+pub struct Receiver<'a, T>(&'a Oneshot<T>);
+
+unsafe impl<'a, T: Send> Send for Receiver<'a, T> {}
+
+impl<'a, T> core::fmt::Debug for Receiver<'a, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Receiver")
+    }
+}
+
+#[cfg(feature = "defmt-03")]
+impl<'a, T> defmt::Format for Receiver<'a, T> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "Receiver",)
+    }
+}
+
+impl<'a, T> Receiver<'a, T> {
+    /// Receives the value if it has been sent, non-blocking.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        critical_section::with(|cs| {
+            let access = self.0.access(cs);
+
+            if let Some(val) = access.value.take() {
+                Ok(val)
+            } else if *access.sender_dropped {
+                Err(TryRecvError::Canceled)
+            } else {
+                Err(TryRecvError::Empty)
+            }
+        })
+    }
+
+    /// Returns true if the sender has been dropped, with or without sending a value.
+    pub fn is_closed(&self) -> bool {
+        critical_section::with(|cs| *self.0.access(cs).sender_dropped)
+    }
+}
+
+impl<'a, T> Future for Receiver<'a, T> {
+    type Output = Result<T, Canceled>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Register waker.
+        self.0.receiver_waker.register(cx.waker());
+
+        match self.get_mut().try_recv() {
+            Ok(val) => Poll::Ready(Ok(val)),
+            Err(TryRecvError::Canceled) => Poll::Ready(Err(Canceled)),
+            Err(TryRecvError::Empty) => Poll::Pending,
+        }
+    }
+}
+
+impl<'a, T> Drop for Receiver<'a, T> {
+    fn drop(&mut self) {
+        // Mark the receiver as dropped, and drop a value that was not received.
+        let val = critical_section::with(|cs| {
+            let access = self.0.access(cs);
+            *access.receiver_dropped = true;
+            self.0.receiver_waker.clear();
+
+            // Free the channel if this was the last half.
+            if *access.sender_dropped {
+                self.0.in_use.store(false, Ordering::Relaxed);
+            }
+
+            access.value.take()
+        });
+
+        drop(val);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn send_receive() {
+        let oneshot = Oneshot::new();
+        let (s, mut r) = oneshot.split().unwrap();
+
+        assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+        assert!(!r.is_closed());
+
+        s.send(1).unwrap();
+
+        assert!(r.is_closed());
+        assert_eq!(r.try_recv(), Ok(1));
+        assert_eq!(r.try_recv(), Err(TryRecvError::Canceled));
+    }
+
+    #[test]
+    fn canceled() {
+        let oneshot = Oneshot::<u32>::new();
+
+        let (s, mut r) = oneshot.split().unwrap();
+        drop(s);
+        assert_eq!(r.try_recv(), Err(TryRecvError::Canceled));
+        drop(r);
+
+        let (s, r) = oneshot.split().unwrap();
+        assert!(!s.is_canceled());
+        drop(r);
+        assert!(s.is_canceled());
+        assert_eq!(s.send(1), Err(1));
+    }
+
+    #[test]
+    fn split_again() {
+        let oneshot = Oneshot::<u32>::new();
+
+        let (s, r) = oneshot.split().unwrap();
+        assert!(oneshot.split().is_none());
+
+        // Both halves have to be dropped, in any order
+        s.send(1).unwrap();
+        assert!(oneshot.split().is_none());
+        drop(r);
+
+        let (s, mut r) = oneshot.split().unwrap();
+        assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+        drop(r);
+        assert!(oneshot.split().is_none());
+        drop(s);
+
+        assert!(oneshot.split().is_some());
+    }
+
+    #[tokio::test]
+    async fn await_reply() {
+        static ONESHOT: Oneshot<u32> = Oneshot::new();
+
+        for i in 0..3 {
+            let (s, r) = ONESHOT.split().unwrap();
+
+            let reply = tokio::spawn(async move {
+                tokio::task::yield_now().await;
+                s.send(i).unwrap();
+            });
+
+            assert_eq!(r.await, Ok(i));
+            reply.await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn await_canceled() {
+        static ONESHOT: Oneshot<u32> = Oneshot::new();
+
+        let (s, r) = ONESHOT.split().unwrap();
+
+        let reply = tokio::spawn(async move {
+            tokio::task::yield_now().await;
+            drop(s);
+        });
+
+        assert_eq!(r.await, Err(Canceled));
+        reply.await.unwrap();
+    }
+}
@@ -0,0 +1,580 @@
+//! An async aware SPSC byte pipe that can be used on no-alloc systems.
+//!
+//! The pipe is a ring buffer of `N` bytes. The [`Writer`] and [`Reader`] halves implement the
+//! `embedded-io-async` traits, the writer waits for the reader to make room and the reader waits
+//! for the writer to fill it. [`Writer::try_write`] never waits, for writing from hardware tasks.
+//!
+//! Example usage:
+//!
+//! ```rust
+//! use embedded_io_async::Read;
+//! use rtic_sync::pipe::{Pipe, Reader, Writer};
+//!
+//! // In the UART hardware task.
+//! fn on_rx(writer: &mut Writer<'static, 64>, received: &[u8]) {
+//!     // Bytes that do not fit are dropped.
+//!     writer.try_write(received).ok();
+//! }
+//!
+//! async fn parser(reader: &mut Reader<'static, 64>) {
+//!     let mut header = [0; 4];
+//!     reader.read_exact(&mut header).await.ok();
+//! }
+//!
+//! static PIPE: Pipe<64> = Pipe::new();
+//!
+//! fn init() -> (Writer<'static, 64>, Reader<'static, 64>) {
+//!     PIPE.split().unwrap()
+//! }
+//! ```
+
+use core::cell::UnsafeCell;
+use core::future::poll_fn;
+use core::ptr;
+use core::task::Poll;
+
+use embedded_io_async::{BufRead, ErrorKind, ErrorType, Read, Write};
+use portable_atomic::{AtomicBool, Ordering};
+use rtic_common::waker_registration::CriticalSectionWakerRegistration as WakerRegistration;
+
+#[cfg(feature = "defmt-03")]
+use crate::defmt;
+
+/// A byte pipe for use in no-alloc systems. `N` sets the size of the ring buffer.
+///
+/// This pipe uses critical sections to keep track of the ring buffer, the bytes are copied in
+/// and out of it without critical sections.
+pub struct Pipe<const N: usize> {
+    // The ring buffer, the bytes in use start at `head` and wrap around.
+    buf: UnsafeCell<[u8; N]>,
+    // The position of the first byte in use.
+    head: UnsafeCell<usize>,
+    // The number of bytes in use.
+    len: UnsafeCell<usize>,
+    // Waker for the reader.
+    reader_waker: WakerRegistration,
+    // Waker for the writer.
+    writer_waker: WakerRegistration,
+    // Keep track of the reader.
+    reader_dropped: UnsafeCell<bool>,
+    // Keep track of the writer.
+    writer_dropped: UnsafeCell<bool>,
+    // The pipe has been split, and not both halves have been dropped yet.
+    in_use: AtomicBool,
+}
+
+unsafe impl<const N: usize> Send for Pipe<N> {}
+
+unsafe impl<const N: usize> Sync for Pipe<N> {}
+
+struct UnsafeAccess<'a> {
+    head: &'a mut usize,
+    len: &'a mut usize,
+    reader_dropped: &'a mut bool,
+    writer_dropped: &'a mut bool,
+}
+
+impl<const N: usize> Default for Pipe<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Pipe<N> {
+    /// Create a new pipe.
+    pub const fn new() -> Self {
+        assert!(N > 0, "The pipe needs at least 1 byte");
+
+        Self {
+            buf: UnsafeCell::new([0; N]),
+            head: UnsafeCell::new(0),
+            len: UnsafeCell::new(0),
+            reader_waker: WakerRegistration::new(),
+            writer_waker: WakerRegistration::new(),
+            reader_dropped: UnsafeCell::new(false),
+            writer_dropped: UnsafeCell::new(false),
+            in_use: AtomicBool::new(false),
+        }
+    }
+
+    /// Split the pipe into a `Writer`/`Reader` pair.
+    ///
+    /// Returns `None` if the pipe is still in use, it can be split again once both halves of the
+    /// previous split have been dropped, it is then empty.
+    pub fn split(&self) -> Option<(Writer<'_, N>, Reader<'_, N>)> {
+        critical_section::with(|cs| {
+            if self.in_use.load(Ordering::Relaxed) {
+                return None;
+            }
+
+            self.in_use.store(true, Ordering::Relaxed);
+
+            let access = self.access(cs);
+            *access.head = 0;
+            *access.len = 0;
+            *access.reader_dropped = false;
+            *access.writer_dropped = false;
+
+            Some((Writer(self), Reader(self)))
+        })
+    }
+
+    /// The contiguous free space after the bytes in use.
+    fn free_chunk(&self) -> (usize, usize) {
+        critical_section::with(|cs| {
+            let access = self.access(cs);
+            let tail = (*access.head + *access.len) % N;
+            let free = N - *access.len;
+
+            (tail, free.min(N - tail))
+        })
+    }
+
+    /// The contiguous bytes in use from `head`.
+    fn used_chunk(&self) -> (usize, usize) {
+        critical_section::with(|cs| {
+            let access = self.access(cs);
+
+            (*access.head, (*access.len).min(N - *access.head))
+        })
+    }
+
+    /// Copies as much of `data` as fits into the free space, returns the number of bytes copied.
+    fn write_bytes(&self, data: &[u8]) -> usize {
+        let mut written = 0;
+
+        // The free space wraps around at most once.
+        for _ in 0..2 {
+            let (tail, free) = self.free_chunk();
+            let n = free.min(data.len() - written);
+            if n == 0 {
+                break;
+            }
+
+            // SAFETY: The free space is only accessed by the writer, and the bytes are made
+            // available to the reader after they are copied.
+            unsafe {
+                ptr::copy_nonoverlapping(
+                    data.as_ptr().add(written),
+                    (self.buf.get() as *mut u8).add(tail),
+                    n,
+                )
+            };
+
+            critical_section::with(|cs| *self.access(cs).len += n);
+            written += n;
+        }
+
+        if written > 0 {
+            self.reader_waker.wake();
+        }
+
+        written
+    }
+
+    /// Copies as many bytes in use as fit into `buf`, returns the number of bytes copied.
+    fn read_bytes(&self, buf: &mut [u8]) -> usize {
+        let mut read = 0;
+
+        // The bytes in use wrap around at most once.
+        for _ in 0..2 {
+            let (head, used) = self.used_chunk();
+            let n = used.min(buf.len() - read);
+            if n == 0 {
+                break;
+            }
+
+            // SAFETY: The bytes in use are only accessed by the reader, and they are given back
+            // to the writer after they are copied.
+            unsafe {
+                ptr::copy_nonoverlapping(
+                    (self.buf.get() as *const u8).add(head),
+                    buf.as_mut_ptr().add(read),
+                    n,
+                )
+            };
+
+            self.consume_bytes(n);
+            read += n;
+        }
+
+        read
+    }
+
+    /// Gives `n` bytes in use back to the writer.
+    fn consume_bytes(&self, n: usize) {
+        critical_section::with(|cs| {
+            let access = self.access(cs);
+            assert!(n <= *access.len);
+
+            *access.head = (*access.head + n) % N;
+            *access.len -= n;
+        });
+
+        if n > 0 {
+            self.writer_waker.wake();
+        }
+    }
+
+    fn access<'a>(&'a self, _cs: critical_section::CriticalSection) -> UnsafeAccess<'a> {
+        // SAFETY: This is safe as are in a critical section.
+        unsafe {
+            UnsafeAccess {
+                head: &mut *self.head.get(),
+                len: &mut *self.len.get(),
+                reader_dropped: &mut *self.reader_dropped.get(),
+                writer_dropped: &mut *self.writer_dropped.get(),
+            }
+        }
+    }
+}
+
+// -------- Writer
+
+/// Error state for when the reader has been dropped.
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct NoReader;
+
+impl embedded_io_async::Error for NoReader {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::BrokenPipe
+    }
+}
+
+/// The writing half of a pipe. There can only be one writer at any time.
+pub struct Writer<'a, const N: usize>(&'a Pipe<N>);
+
+unsafe impl<'a, const N: usize> Send for Writer<'a, N> {}
+
+impl<'a, const N: usize> core::fmt::Debug for Writer<'a, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Writer")
+    }
+}
+
+#[cfg(feature = "defmt-03")]
+impl<'a, const N: usize> defmt::Format for Writer<'a, N> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "Writer",)
+    }
+}
+
+impl<'a, const N: usize> Writer<'a, N> {
+    /// Write as many bytes as fit in the pipe, non-blocking. Returns the number of bytes written,
+    /// which is 0 if the pipe is full.
+    /// If the reader does not exist this will return an error.
+    pub fn try_write(&mut self, data: &[u8]) -> Result<usize, NoReader> {
+        if self.is_closed() {
+            return Err(NoReader);
+        }
+
+        Ok(self.0.write_bytes(data))
+    }
+
+    /// Returns true if there is no `Reader`.
+    pub fn is_closed(&self) -> bool {
+        critical_section::with(|cs| *self.0.access(cs).reader_dropped)
+    }
+
+    /// The number of bytes that can be written without waiting.
+    pub fn free(&self) -> usize {
+        critical_section::with(|cs| N - *self.0.access(cs).len)
+    }
+}
+
+impl<'a, const N: usize> ErrorType for Writer<'a, N> {
+    type Error = NoReader;
+}
+
+impl<'a, const N: usize> Write for Writer<'a, N> {
+    /// Write as many bytes as fit in the pipe, waiting until at least one fits.
+    async fn write(&mut self, data: &[u8]) -> Result<usize, Self::Error> {
+        if data.is_empty() {
+            return Ok(0);
+        }
+
+        poll_fn(|cx| {
+            // Register waker.
+            self.0.writer_waker.register(cx.waker());
+
+            match self.try_write(data) {
+                Ok(0) => Poll::Pending,
+                written => Poll::Ready(written),
+            }
+        })
+        .await
+    }
+
+    /// Wait until the reader has read all bytes in the pipe.
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        poll_fn(|cx| {
+            // Register waker.
+            self.0.writer_waker.register(cx.waker());
+
+            if self.is_closed() {
+                Poll::Ready(Err(NoReader))
+            } else if self.free() == N {
+                Poll::Ready(Ok(()))
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+impl<'a, const N: usize> Drop for Writer<'a, N> {
+    fn drop(&mut self) {
+        critical_section::with(|cs| {
+            let access = self.0.access(cs);
+            *access.writer_dropped = true;
+
+            // Free the pipe if this was the last half.
+            if *access.reader_dropped {
+                self.0.in_use.store(false, Ordering::Relaxed);
+            }
+        });
+
+        // Wake the reader, to read the rest of the bytes or to see the end of the pipe.
+        self.0.reader_waker.wake();
+    }
+}
+
+// -------- Reader
+
+/// The reading half of a pipe. There can only be one reader at any time.
+///
+/// Once the writer is dropped and all bytes are read, reads return 0 bytes.
+pub struct Reader<'a, const N: usize>(&'a Pipe<N>);
+
+unsafe impl<'a, const N: usize> Send for Reader<'a, N> {}
+
+impl<'a, const N: usize> core::fmt::Debug for Reader<'a, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Reader")
+    }
+}
+
+#[cfg(feature = "defmt-03")]
+impl<'a, const N: usize> defmt::Format for Reader<'a, N> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "Reader",)
+    }
+}
+
+impl<'a, const N: usize> Reader<'a, N> {
+    /// Read as many bytes as are in the pipe, non-blocking. Returns the number of bytes read.
+    pub fn try_read(&mut self, buf: &mut [u8]) -> usize {
+        self.0.read_bytes(buf)
+    }
+
+    /// Returns true if there is no `Writer`.
+    pub fn is_closed(&self) -> bool {
+        critical_section::with(|cs| *self.0.access(cs).writer_dropped)
+    }
+
+    /// The number of bytes that can be read without waiting.
+    pub fn len(&self) -> usize {
+        critical_section::with(|cs| *self.0.access(cs).len)
+    }
+
+    /// Is the pipe empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Wait until there are bytes in the pipe, or the writer is dropped.
+    async fn wait(&mut self) {
+        poll_fn(|cx| {
+            // Register waker.
+            self.0.reader_waker.register(cx.waker());
+
+            if !self.is_empty() || self.is_closed() {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+impl<'a, const N: usize> ErrorType for Reader<'a, N> {
+    type Error = core::convert::Infallible;
+}
+
+impl<'a, const N: usize> Read for Reader<'a, N> {
+    /// Read as many bytes as are in the pipe, waiting until there is at least one.
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        self.wait().await;
+
+        Ok(self.try_read(buf))
+    }
+}
+
+impl<'a, const N: usize> BufRead for Reader<'a, N> {
+    /// Wait until there are bytes in the pipe, and return the contiguous ones.
+    async fn fill_buf(&mut self) -> Result<&[u8], Self::Error> {
+        self.wait().await;
+
+        let (head, used) = self.0.used_chunk();
+
+        // SAFETY: The bytes in use are only accessed by the reader, and they stay in use until
+        // they are consumed, which needs `&mut self`.
+        Ok(unsafe { core::slice::from_raw_parts((self.0.buf.get() as *const u8).add(head), used) })
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.0.consume_bytes(amt);
+    }
+}
+
+impl<'a, const N: usize> Drop for Reader<'a, N> {
+    fn drop(&mut self) {
+        critical_section::with(|cs| {
+            let access = self.0.access(cs);
+            *access.reader_dropped = true;
+
+            // Free the pipe if this was the last half.
+            if *access.writer_dropped {
+                self.0.in_use.store(false, Ordering::Relaxed);
+            }
+        });
+
+        // Wake the writer, to do error handling.
+        self.0.writer_waker.wake();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_around() {
+        let pipe = Pipe::<4>::new();
+        let (mut w, mut r) = pipe.split().unwrap();
+        let mut buf = [0; 4];
+
+        assert_eq!(w.try_write(&[1, 2, 3]), Ok(3));
+        assert_eq!(r.try_read(&mut buf[..2]), 2);
+        assert_eq!(buf[..2], [1, 2]);
+
+        // Wraps around the end of the ring buffer
+        assert_eq!(w.try_write(&[4, 5, 6, 7]), Ok(3));
+        assert_eq!(w.free(), 0);
+        assert_eq!(w.try_write(&[7]), Ok(0));
+
+        assert_eq!(r.len(), 4);
+        assert_eq!(r.try_read(&mut buf), 4);
+        assert_eq!(buf, [3, 4, 5, 6]);
+        assert!(r.is_empty());
+    }
+
+    #[test]
+    fn closed() {
+        let pipe = Pipe::<4>::new();
+
+        let (mut w, r) = pipe.split().unwrap();
+        drop(r);
+        assert!(w.is_closed());
+        assert_eq!(w.try_write(&[1]), Err(NoReader));
+        drop(w);
+
+        let (mut w, mut r) = pipe.split().unwrap();
+        assert_eq!(w.try_write(&[1]), Ok(1));
+        drop(w);
+        assert!(r.is_closed());
+        assert_eq!(r.try_read(&mut [0; 4]), 1);
+    }
+
+    #[test]
+    fn split_again() {
+        let pipe = Pipe::<4>::new();
+
+        let (mut w, r) = pipe.split().unwrap();
+        assert!(pipe.split().is_none());
+        assert_eq!(w.try_write(&[1]), Ok(1));
+
+        drop(w);
+        assert!(pipe.split().is_none());
+        drop(r);
+
+        // Empty again
+        let (w, r) = pipe.split().unwrap();
+        assert_eq!(w.free(), 4);
+        assert!(r.is_empty());
+    }
+
+    #[tokio::test]
+    async fn eof() {
+        static PIPE: Pipe<4> = Pipe::new();
+
+        let (mut w, mut r) = PIPE.split().unwrap();
+
+        w.write_all(&[1, 2]).await.unwrap();
+        drop(w);
+
+        let mut buf = [0; 4];
+        assert_eq!(r.read(&mut buf).await, Ok(2));
+        assert_eq!(r.read(&mut buf).await, Ok(0));
+    }
+
+    #[tokio::test]
+    async fn fill_buf() {
+        static PIPE: Pipe<4> = Pipe::new();
+
+        let (mut w, mut r) = PIPE.split().unwrap();
+
+        w.write_all(&[1, 2, 3]).await.unwrap();
+        r.consume(2);
+        w.write_all(&[4, 5, 6]).await.unwrap();
+
+        // The contiguous bytes up to the end of the ring buffer
+        assert_eq!(r.fill_buf().await, Ok(&[3, 4][..]));
+        r.consume(2);
+        assert_eq!(r.fill_buf().await, Ok(&[5, 6][..]));
+    }
+
+    #[tokio::test]
+    async fn stress_pipe() {
+        const NUM_BYTES: usize = 10_000;
+
+        static PIPE: Pipe<7> = Pipe::new();
+
+        let (mut w, mut r) = PIPE.split().unwrap();
+
+        let writer = tokio::spawn(async move {
+            let data: std::vec::Vec<u8> = (0..NUM_BYTES).map(|i| i as u8).collect();
+
+            for chunk in data.chunks(5) {
+                w.write_all(chunk).await.unwrap();
+                tokio::task::yield_now().await;
+            }
+
+            w.flush().await.unwrap();
+        });
+
+        let mut buf = [0; 3];
+        let mut received = 0;
+        loop {
+            let n = r.read(&mut buf).await.unwrap();
+            if n == 0 {
+                break;
+            }
+
+            for b in &buf[..n] {
+                assert_eq!(*b, received as u8);
+                received += 1;
+            }
+        }
+
+        assert_eq!(received, NUM_BYTES);
+        writer.await.unwrap();
+    }
+}
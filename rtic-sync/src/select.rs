@@ -0,0 +1,222 @@
+//! Wait for the first of several futures to complete, on no-alloc systems.
+//!
+//! The futures are polled in the order they are given, so if several are ready at once the first
+//! one wins. As soon as one completes, the others are dropped in place before the result is
+//! returned, which removes them from any wait queue they are in: a pending `Sender::send` or
+//! `Arbiter::access` gives up its place, and an `Arbiter` access that was handed over but not
+//! taken yet is passed on to the next in queue.
+//!
+//! The futures do not need to be fused and are not polled after one of them completes.
+//!
+//! Example usage:
+//!
+//! ```rust
+//! use rtic_sync::arbiter::Arbiter;
+//! use rtic_sync::channel::Receiver;
+//! use rtic_sync::select::{select3, Either3};
+//! use rtic_time::Monotonic;
+//!
+//! static BUS: Arbiter<u32> = Arbiter::new(0);
+//!
+//! async fn run<Mono: Monotonic>(
+//!     commands: &mut Receiver<'static, u8, 4>,
+//!     deadline: Mono::Instant,
+//! ) {
+//!     match select3(commands.recv(), BUS.access(), Mono::delay_until(deadline)).await {
+//!         Either3::First(command) => { /* Handle `command` */ }
+//!         Either3::Second(bus) => { /* Use the `bus` */ }
+//!         Either3::Third(()) => { /* The deadline has passed */ }
+//!     }
+//! }
+//! ```
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+#[cfg(feature = "defmt-03")]
+use crate::defmt;
+
+macro_rules! select {
+    (
+        $(#[$fn_attr:meta])*
+        fn $name:ident;
+        $(#[$select_attr:meta])*
+        struct $Select:ident;
+        $(#[$either_attr:meta])*
+        enum $Either:ident;
+        $($F:ident $f:ident $Variant:ident,)+
+    ) => {
+        $(#[$either_attr])*
+        #[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+        #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+        pub enum $Either<$($F),+> {
+            $(
+                #[allow(missing_docs)]
+                $Variant($F),
+            )+
+        }
+
+        $(#[$select_attr])*
+        #[must_use = "futures do nothing unless you `.await` or poll them"]
+        pub struct $Select<$($F),+> {
+            $($f: Option<$F>,)+
+        }
+
+        $(#[$fn_attr])*
+        pub fn $name<$($F: Future),+>($($f: $F),+) -> $Select<$($F),+> {
+            $Select {
+                $($f: Some($f),)+
+            }
+        }
+
+        impl<$($F),+> $Select<$($F),+> {
+            /// Drops all the futures in place.
+            fn finish(&mut self) {
+                $(self.$f = None;)+
+            }
+        }
+
+        impl<$($F: Future),+> Future for $Select<$($F),+> {
+            type Output = $Either<$($F::Output),+>;
+
+            fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+                // SAFETY: The futures are never moved, they are only dropped in place.
+                let this = unsafe { self.get_unchecked_mut() };
+
+                $(
+                    let $f = this
+                        .$f
+                        .as_mut()
+                        .expect(concat!("`", stringify!($Select), "` polled after completion"));
+
+                    // SAFETY: The future is pinned as `self` is pinned.
+                    if let Poll::Ready(val) = unsafe { Pin::new_unchecked($f) }.poll(cx) {
+                        this.finish();
+
+                        return Poll::Ready($Either::$Variant(val));
+                    }
+                )+
+
+                Poll::Pending
+            }
+        }
+    };
+}
+
+select! {
+    /// Wait for the first of two futures to complete, the other one is dropped.
+    fn select;
+    /// Future for the [`select`] function.
+    struct Select;
+    /// The result of [`select`], telling which future completed.
+    enum Either;
+    A a First,
+    B b Second,
+}
+
+select! {
+    /// Wait for the first of three futures to complete, the others are dropped.
+    fn select3;
+    /// Future for the [`select3`] function.
+    struct Select3;
+    /// The result of [`select3`], telling which future completed.
+    enum Either3;
+    A a First,
+    B b Second,
+    C c Third,
+}
+
+select! {
+    /// Wait for the first of four futures to complete, the others are dropped.
+    fn select4;
+    /// Future for the [`select4`] function.
+    struct Select4;
+    /// The result of [`select4`], telling which future completed.
+    enum Either4;
+    A a First,
+    B b Second,
+    C c Third,
+    D d Fourth,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbiter::Arbiter;
+    use crate::make_channel;
+    use core::future::{pending, ready};
+
+    #[tokio::test]
+    async fn biased() {
+        assert_eq!(select(ready(1), ready(2)).await, Either::First(1));
+        assert_eq!(select(pending::<()>(), ready(2)).await, Either::Second(2));
+        assert_eq!(
+            select4(pending::<()>(), pending::<()>(), pending::<()>(), ready(4)).await,
+            Either4::Fourth(4)
+        );
+    }
+
+    #[tokio::test]
+    async fn receivers() {
+        let (_s1, mut r1) = make_channel!(u32, 4);
+        let (mut s2, mut r2) = make_channel!(u32, 4);
+
+        s2.try_send(2).unwrap();
+
+        assert_eq!(
+            select3(
+                r1.recv(),
+                r2.recv(),
+                tokio::time::sleep(core::time::Duration::MAX)
+            )
+            .await,
+            Either3::Second(Ok(2))
+        );
+
+        let timeout = tokio::time::sleep(core::time::Duration::from_millis(1));
+        assert_eq!(
+            select3(r1.recv(), r2.recv(), timeout).await,
+            Either3::Third(())
+        );
+    }
+
+    #[tokio::test]
+    async fn dropped_access_leaves_queue_clean() {
+        static ARBITER: Arbiter<u32> = Arbiter::new(0);
+
+        let access = ARBITER.access().await;
+
+        // The access loses, and gives up its place in the queue
+        let timeout = tokio::time::sleep(core::time::Duration::from_millis(1));
+        assert!(matches!(
+            select(ARBITER.access(), timeout).await,
+            Either::Second(())
+        ));
+
+        drop(access);
+        assert!(ARBITER.try_access().is_some());
+    }
+
+    #[test]
+    fn dropped_granted_access_is_passed_on() {
+        static ARBITER: Arbiter<u32> = Arbiter::new(0);
+
+        let access = ARBITER.try_access().unwrap();
+        let (mut s, mut r) = make_channel!(u32, 4);
+
+        let mut select = core::pin::pin!(select(r.recv(), ARBITER.access()));
+        let mut cx = Context::from_waker(futures_util::task::noop_waker_ref());
+        assert!(select.as_mut().poll(&mut cx).is_pending());
+
+        // Access is handed over to the second branch, but the first branch wins
+        s.try_send(1).unwrap();
+        drop(access);
+        assert!(matches!(
+            select.as_mut().poll(&mut cx),
+            Poll::Ready(Either::First(Ok(1)))
+        ));
+
+        assert!(ARBITER.try_access().is_some());
+    }
+}
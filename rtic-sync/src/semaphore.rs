@@ -0,0 +1,335 @@
+//! An async counting semaphore for embedded systems.
+//!
+//! Example usage:
+//!
+//! ```rust
+//! use rtic_sync::semaphore::Semaphore;
+//!
+//! // At most 4 DMA descriptors in use at any time.
+//! static DESCRIPTORS: Semaphore = Semaphore::new(4);
+//!
+//! async fn transfer() {
+//!     // Waits until 2 descriptors are free, they are given back when `permit` is dropped.
+//!     let permit = DESCRIPTORS.acquire(2).await;
+//!     // Use the descriptors
+//! }
+//! ```
+
+use core::cell::UnsafeCell;
+use core::future::poll_fn;
+use core::pin::Pin;
+use core::task::{Poll, Waker};
+
+use rtic_common::dropper::OnDrop;
+use rtic_common::wait_queue::{Link, WaitQueue};
+use rtic_common::waker_registration::CriticalSectionWakerRegistration as WakerRegistration;
+
+/// This is needed to make the async closure in `acquire` accept that we "share"
+/// the link possible between threads.
+#[derive(Clone)]
+struct LinkPtr(*mut Option<Link<Waker>>);
+
+impl LinkPtr {
+    /// This will dereference the pointer stored within and give out an `&mut`.
+    unsafe fn get(&mut self) -> &mut Option<Link<Waker>> {
+        &mut *self.0
+    }
+}
+
+unsafe impl Send for LinkPtr {}
+unsafe impl Sync for LinkPtr {}
+
+/// A counting semaphore with a FIFO queue of waiters.
+///
+/// Waiters are served strictly in order: the first waiter in the queue gets its permits before
+/// any later waiter, even if a later waiter asks for fewer permits.
+pub struct Semaphore {
+    // Waiters that are not first in line.
+    wait_queue: WaitQueue,
+    // Waker of the waiter that has been popped from the queue and is first in line.
+    head_waker: WakerRegistration,
+    // There is a waiter first in line, which has to be served before anyone else.
+    head_waiting: UnsafeCell<bool>,
+    // Number of free permits.
+    permits: UnsafeCell<usize>,
+}
+
+unsafe impl Send for Semaphore {}
+unsafe impl Sync for Semaphore {}
+
+struct UnsafeAccess<'a> {
+    head_waiting: &'a mut bool,
+    permits: &'a mut usize,
+}
+
+impl Semaphore {
+    /// Create a new semaphore with `permits` free permits.
+    pub const fn new(permits: usize) -> Self {
+        Self {
+            wait_queue: WaitQueue::new(),
+            head_waker: WakerRegistration::new(),
+            head_waiting: UnsafeCell::new(false),
+            permits: UnsafeCell::new(permits),
+        }
+    }
+
+    /// Acquire `n` permits, waiting until they are available. The permits are released when the
+    /// returned [`Permit`] is dropped.
+    ///
+    /// Waiting for more permits than the semaphore can ever have waits forever.
+    pub async fn acquire(&self, n: usize) -> Permit<'_> {
+        let mut link_ptr: Option<Link<Waker>> = None;
+
+        // Make this future `Drop`-safe.
+        // SAFETY(link_ptr): Shadow the original definition of `link_ptr` so we can't abuse it.
+        let mut link_ptr = LinkPtr(&mut link_ptr as *mut Option<Link<Waker>>);
+
+        let mut link_ptr2 = link_ptr.clone();
+        let dropper = OnDrop::new(|| {
+            critical_section::with(|cs| {
+                // SAFETY: We only run this closure and dereference the pointer if we have
+                // exited the `poll_fn` below in the `drop(dropper)` call. The other dereference
+                // of this pointer is in the `poll_fn`.
+                if let Some(link) = unsafe { link_ptr2.get() } {
+                    if link.is_popped() {
+                        // First in line, but dropped before getting the permits.
+                        *self.access(cs).head_waiting = false;
+                        self.wake_next(cs);
+                    } else {
+                        link.remove_from_list(&self.wait_queue);
+                    }
+                }
+            })
+        });
+
+        poll_fn(|cx| {
+            critical_section::with(|cs| {
+                // SAFETY: This pointer is only dereferenced here and on drop of the future
+                // which happens outside this `poll_fn`'s stack frame.
+                let link = unsafe { link_ptr.get() };
+                let access = self.access(cs);
+
+                match link {
+                    None => {
+                        // Noone is in line and there are enough permits.
+                        if self.wait_queue.is_empty()
+                            && !*access.head_waiting
+                            && *access.permits >= n
+                        {
+                            *access.permits -= n;
+
+                            return Poll::Ready(());
+                        }
+
+                        // Place the link in the wait queue on first run.
+                        let link_ref = link.insert(Link::new(cx.waker().clone()));
+
+                        // SAFETY(new_unchecked): The address to the link is stable as it is defined
+                        // outside this stack frame.
+                        // SAFETY(push): `link_ref` lifetime comes from `link_ptr` that is shadowed,
+                        // and  we make sure in `dropper` that the link is removed from the queue
+                        // before dropping `link_ptr` AND `dropper` makes sure that the shadowed
+                        // `link_ptr` lives until the end of the stack frame.
+                        unsafe { self.wait_queue.push(Pin::new_unchecked(link_ref)) };
+
+                        // There may be no release to pop the link, make sure it is popped.
+                        if !*access.head_waiting {
+                            self.wake_next(cs);
+                        }
+                    }
+                    Some(link) if link.is_popped() => {
+                        // First in line.
+                        if *access.permits >= n {
+                            *access.permits -= n;
+                            *access.head_waiting = false;
+
+                            // The next in line may be satisfied by the remaining permits.
+                            self.wake_next(cs);
+
+                            return Poll::Ready(());
+                        }
+
+                        self.head_waker.register(cx.waker());
+                    }
+                    Some(_) => {}
+                }
+
+                Poll::Pending
+            })
+        })
+        .await;
+
+        // The permits have been taken, there is nothing to clean up.
+        dropper.defuse();
+
+        Permit { semaphore: self, n }
+    }
+
+    /// Non-blockingly tries to acquire `n` permits.
+    /// If someone is in line for permits, this will return `None`.
+    pub fn try_acquire(&self, n: usize) -> Option<Permit<'_>> {
+        critical_section::with(|cs| {
+            let access = self.access(cs);
+
+            if self.wait_queue.is_empty() && !*access.head_waiting && *access.permits >= n {
+                *access.permits -= n;
+
+                Some(Permit { semaphore: self, n })
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Adds `n` permits to the semaphore.
+    pub fn add_permits(&self, n: usize) {
+        critical_section::with(|cs| {
+            *self.access(cs).permits += n;
+
+            if *self.access(cs).head_waiting {
+                self.head_waker.wake();
+            } else {
+                self.wake_next(cs);
+            }
+        })
+    }
+
+    /// The number of free permits.
+    pub fn available_permits(&self) -> usize {
+        critical_section::with(|cs| *self.access(cs).permits)
+    }
+
+    /// Pops the next waiter from the queue, if any, and makes it first in line.
+    fn wake_next(&self, cs: critical_section::CriticalSection) {
+        if let Some(waker) = self.wait_queue.pop() {
+            *self.access(cs).head_waiting = true;
+            waker.wake();
+        }
+    }
+
+    fn access<'a>(&'a self, _cs: critical_section::CriticalSection) -> UnsafeAccess<'a> {
+        // SAFETY: This is safe as are in a critical section.
+        unsafe {
+            UnsafeAccess {
+                head_waiting: &mut *self.head_waiting.get(),
+                permits: &mut *self.permits.get(),
+            }
+        }
+    }
+}
+
+/// This token represents permits acquired from the [`Semaphore`], which are released on drop.
+pub struct Permit<'a> {
+    semaphore: &'a Semaphore,
+    n: usize,
+}
+
+impl<'a> Permit<'a> {
+    /// The number of permits held.
+    pub fn permits(&self) -> usize {
+        self.n
+    }
+
+    /// Drops the permits without releasing them, removing them from the semaphore.
+    pub fn forget(self) {
+        core::mem::forget(self)
+    }
+}
+
+impl<'a> Drop for Permit<'a> {
+    fn drop(&mut self) {
+        self.semaphore.add_permits(self.n);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_acquire() {
+        let semaphore = Semaphore::new(3);
+
+        let p1 = semaphore.try_acquire(2).unwrap();
+        assert_eq!(p1.permits(), 2);
+        assert_eq!(semaphore.available_permits(), 1);
+        assert!(semaphore.try_acquire(2).is_none());
+
+        drop(p1);
+        assert_eq!(semaphore.available_permits(), 3);
+
+        semaphore.try_acquire(3).unwrap().forget();
+        assert_eq!(semaphore.available_permits(), 0);
+
+        semaphore.add_permits(1);
+        assert!(semaphore.try_acquire(1).is_some());
+    }
+
+    #[tokio::test]
+    async fn fifo() {
+        static SEMAPHORE: Semaphore = Semaphore::new(2);
+
+        let p = SEMAPHORE.acquire(2).await;
+
+        // `big` is first in line, `small` may not overtake it
+        let big = tokio::spawn(async { SEMAPHORE.acquire(2).await.forget() });
+        tokio::task::yield_now().await;
+        let small = tokio::spawn(async { SEMAPHORE.acquire(1).await.forget() });
+        tokio::task::yield_now().await;
+
+        drop(p);
+        big.await.unwrap();
+        assert_eq!(SEMAPHORE.available_permits(), 0);
+        assert!(!small.is_finished());
+
+        SEMAPHORE.add_permits(1);
+        small.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn dropped_head() {
+        static SEMAPHORE: Semaphore = Semaphore::new(1);
+
+        let p = SEMAPHORE.acquire(1).await;
+
+        let first = tokio::spawn(async { SEMAPHORE.acquire(1).await.forget() });
+        tokio::task::yield_now().await;
+        let second = tokio::spawn(async { SEMAPHORE.acquire(1).await.forget() });
+        tokio::task::yield_now().await;
+
+        // The first in line gives up, the permit goes to the second
+        first.abort();
+        let _ = first.await;
+        drop(p);
+
+        second.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn stress_semaphore() {
+        const NUM_RUNS: usize = 10_000;
+        const PERMITS: usize = 3;
+
+        static SEMAPHORE: Semaphore = Semaphore::new(PERMITS);
+        static IN_USE: portable_atomic::AtomicUsize = portable_atomic::AtomicUsize::new(0);
+        let mut v = std::vec::Vec::new();
+
+        for i in 0..NUM_RUNS {
+            v.push(tokio::spawn(async move {
+                let n = i % PERMITS + 1;
+                let _permit = SEMAPHORE.acquire(n).await;
+
+                let in_use = IN_USE.fetch_add(n, portable_atomic::Ordering::Relaxed) + n;
+                assert!(in_use <= PERMITS);
+                tokio::task::yield_now().await;
+                IN_USE.fetch_sub(n, portable_atomic::Ordering::Relaxed);
+            }));
+        }
+
+        for v in v {
+            v.await.unwrap();
+        }
+
+        assert_eq!(SEMAPHORE.available_permits(), PERMITS);
+    }
+}
@@ -0,0 +1,41 @@
+//! A monotonic on top of the tokio timer, for the tests.
+
+use rtic_time::{Monotonic, TimeoutError};
+use tokio::time::Instant;
+
+pub(crate) struct TokioMono;
+
+impl Monotonic for TokioMono {
+    type Instant = Instant;
+    type Duration = core::time::Duration;
+
+    fn now() -> Self::Instant {
+        Instant::now()
+    }
+
+    async fn delay(duration: Self::Duration) {
+        tokio::time::sleep(duration).await
+    }
+
+    async fn delay_until(instant: Self::Instant) {
+        tokio::time::sleep_until(instant).await
+    }
+
+    async fn timeout_at<F: core::future::Future>(
+        instant: Self::Instant,
+        future: F,
+    ) -> Result<F::Output, TimeoutError> {
+        tokio::time::timeout_at(instant, future)
+            .await
+            .map_err(|_| TimeoutError)
+    }
+
+    async fn timeout_after<F: core::future::Future>(
+        duration: Self::Duration,
+        future: F,
+    ) -> Result<F::Output, TimeoutError> {
+        tokio::time::timeout(duration, future)
+            .await
+            .map_err(|_| TimeoutError)
+    }
+}
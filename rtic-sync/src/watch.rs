@@ -0,0 +1,378 @@
+//! A single-slot "latest value" channel that can be used on no-alloc systems.
+//!
+//! Sending overwrites the value in the slot and never waits, receivers wait for a change and then
+//! read the latest value. Values sent in between are lost, which is what is wanted for sensor
+//! readings and configuration, where only the newest value matters.
+//!
+//! Example usage:
+//!
+//! ```rust
+//! use rtic_sync::watch::Watch;
+//!
+//! static CONFIG: Watch<u32> = Watch::new();
+//!
+//! async fn producer() {
+//!     let sender = CONFIG.sender();
+//!     sender.send(1);
+//!     // Overwrites the value, even if it has not been received
+//!     sender.send(2);
+//! }
+//!
+//! async fn consumer() {
+//!     let mut receiver = CONFIG.receiver();
+//!     loop {
+//!         let config = receiver.changed().await;
+//!         // Apply `config`
+//!     }
+//! }
+//! ```
+
+use core::cell::UnsafeCell;
+use core::future::poll_fn;
+use core::pin::Pin;
+use core::task::{Poll, Waker};
+
+use rtic_common::dropper::OnDrop;
+use rtic_common::wait_queue::{Link, WaitQueue};
+
+#[cfg(feature = "defmt-03")]
+use crate::defmt;
+
+/// This is needed to make the async closure in `changed` accept that we "share"
+/// the link possible between threads.
+#[derive(Clone)]
+struct LinkPtr(*mut Option<Link<Waker>>);
+
+impl LinkPtr {
+    /// This will dereference the pointer stored within and give out an `&mut`.
+    unsafe fn get(&mut self) -> &mut Option<Link<Waker>> {
+        &mut *self.0
+    }
+}
+
+unsafe impl Send for LinkPtr {}
+unsafe impl Sync for LinkPtr {}
+
+/// A single-slot channel with any number of senders and receivers, where every receiver sees the
+/// latest value.
+///
+/// All accesses to the slot are done in critical sections, `T` is cloned out of the slot by the
+/// receivers.
+pub struct Watch<T> {
+    // The latest value, `None` until the first value is sent.
+    value: UnsafeCell<Option<T>>,
+    // Incremented on every send, 0 means that no value has been sent.
+    version: UnsafeCell<usize>,
+    // Receivers waiting for a change.
+    wait_queue: WaitQueue,
+}
+
+unsafe impl<T: Send> Send for Watch<T> {}
+
+unsafe impl<T: Send> Sync for Watch<T> {}
+
+impl<T> Default for Watch<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Watch<T> {
+    /// Create a new watch without a value.
+    pub const fn new() -> Self {
+        Self {
+            value: UnsafeCell::new(None),
+            version: UnsafeCell::new(0),
+            wait_queue: WaitQueue::new(),
+        }
+    }
+
+    /// Create a new watch with an initial value, which is seen as a change by the receivers.
+    pub const fn new_with(value: T) -> Self {
+        Self {
+            value: UnsafeCell::new(Some(value)),
+            version: UnsafeCell::new(1),
+            wait_queue: WaitQueue::new(),
+        }
+    }
+
+    /// Create a sender of the watch.
+    pub fn sender(&self) -> Sender<'_, T> {
+        Sender(self)
+    }
+
+    /// Create a receiver of the watch. The current value, if any, is seen as a change.
+    pub fn receiver(&self) -> Receiver<'_, T> {
+        Receiver {
+            watch: self,
+            seen: 0,
+        }
+    }
+
+    fn version(&self, _cs: critical_section::CriticalSection) -> usize {
+        // SAFETY: This is safe as are in a critical section.
+        unsafe { *self.version.get() }
+    }
+}
+
+// -------- Sender
+
+/// A `Sender` overwrites the value of the watch and can be cloned.
+pub struct Sender<'a, T>(&'a Watch<T>);
+
+impl<'a, T> Clone for Sender<'a, T> {
+    fn clone(&self) -> Self {
+        Self(self.0)
+    }
+}
+
+impl<'a, T> core::fmt::Debug for Sender<'a, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Sender")
+    }
+}
+
+#[cfg(feature = "defmt-03")]
+impl<'a, T> defmt::Format for Sender<'a, T> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "Sender",)
+    }
+}
+
+impl<'a, T> Sender<'a, T> {
+    /// Send a value, overwriting the previous one, and wake all waiting receivers. This never
+    /// waits.
+    pub fn send(&self, val: T) {
+        let old = critical_section::with(|_| {
+            // SAFETY: This is safe as are in a critical section.
+            let (value, version) =
+                unsafe { (&mut *self.0.value.get(), &mut *self.0.version.get()) };
+
+            // Version 0 is reserved for a watch without a value.
+            *version = version.wrapping_add(1).max(1);
+
+            value.replace(val)
+        });
+
+        // Drop the previous value outside of the critical section.
+        drop(old);
+
+        while let Some(waker) = self.0.wait_queue.pop() {
+            waker.wake();
+        }
+    }
+}
+
+// -------- Receiver
+
+/// A receiver of the watch, which keeps track of the values it has seen.
+pub struct Receiver<'a, T> {
+    watch: &'a Watch<T>,
+    seen: usize,
+}
+
+impl<'a, T> core::fmt::Debug for Receiver<'a, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Receiver")
+    }
+}
+
+#[cfg(feature = "defmt-03")]
+impl<'a, T> defmt::Format for Receiver<'a, T> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "Receiver",)
+    }
+}
+
+impl<'a, T> Clone for Receiver<'a, T> {
+    fn clone(&self) -> Self {
+        Self {
+            watch: self.watch,
+            seen: self.seen,
+        }
+    }
+}
+
+impl<'a, T: Clone> Receiver<'a, T> {
+    /// Returns the latest value, if any has been sent, and marks it as seen.
+    pub fn get(&mut self) -> Option<T> {
+        critical_section::with(|cs| {
+            self.seen = self.watch.version(cs);
+
+            // SAFETY: This is safe as are in a critical section.
+            unsafe { (*self.watch.value.get()).clone() }
+        })
+    }
+
+    /// Returns the latest value if it has not been seen by this receiver, non-blocking.
+    pub fn try_changed(&mut self) -> Option<T> {
+        critical_section::with(|cs| {
+            let version = self.watch.version(cs);
+            if version == self.seen {
+                return None;
+            }
+
+            self.seen = version;
+
+            // SAFETY: This is safe as are in a critical section.
+            unsafe { (*self.watch.value.get()).clone() }
+        })
+    }
+
+    /// Waits until a value that has not been seen by this receiver is sent, and returns the latest
+    /// value.
+    pub async fn changed(&mut self) -> T {
+        let mut link_ptr: Option<Link<Waker>> = None;
+
+        // Make this future `Drop`-safe.
+        // SAFETY(link_ptr): Shadow the original definition of `link_ptr` so we can't abuse it.
+        let mut link_ptr = LinkPtr(&mut link_ptr as *mut Option<Link<Waker>>);
+
+        let mut link_ptr2 = link_ptr.clone();
+        let dropper = OnDrop::new(|| {
+            // SAFETY: We only run this closure and dereference the pointer if we have
+            // exited the `poll_fn` below in the `drop(dropper)` call. The other dereference
+            // of this pointer is in the `poll_fn`.
+            if let Some(link) = unsafe { link_ptr2.get() } {
+                link.remove_from_list(&self.watch.wait_queue);
+            }
+        });
+
+        let val = poll_fn(|cx| {
+            critical_section::with(|cs| {
+                let version = self.watch.version(cs);
+                if version != self.seen {
+                    self.seen = version;
+
+                    // SAFETY: This is safe as are in a critical section. A non-zero version
+                    // means that a value has been sent.
+                    let val = unsafe { (*self.watch.value.get()).clone() };
+                    return Poll::Ready(val);
+                }
+
+                // SAFETY: This pointer is only dereferenced here and on drop of the future
+                // which happens outside this `poll_fn`'s stack frame.
+                let link = unsafe { link_ptr.get() };
+                if link.as_ref().is_none_or(|link| link.is_popped()) {
+                    // Place the link in the wait queue on first run, or again if it has been
+                    // woken without a change.
+                    let link_ref = link.insert(Link::new(cx.waker().clone()));
+
+                    // SAFETY(new_unchecked): The address to the link is stable as it is defined
+                    // outside this stack frame.
+                    // SAFETY(push): `link_ref` lifetime comes from `link_ptr` that is shadowed,
+                    // and  we make sure in `dropper` that the link is removed from the queue
+                    // before dropping `link_ptr` AND `dropper` makes sure that the shadowed
+                    // `link_ptr` lives until the end of the stack frame.
+                    unsafe { self.watch.wait_queue.push(Pin::new_unchecked(link_ref)) };
+                }
+
+                Poll::Pending
+            })
+        })
+        .await;
+
+        // Make sure the link is removed from the queue.
+        drop(dropper);
+
+        // The version only changes when a value is sent.
+        val.unwrap()
+    }
+}
+
+impl<'a, T> Receiver<'a, T> {
+    /// Returns true if a value that has not been seen by this receiver has been sent.
+    pub fn has_changed(&self) -> bool {
+        critical_section::with(|cs| self.watch.version(cs) != self.seen)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let watch = Watch::<u32>::new();
+        let mut r = watch.receiver();
+
+        assert!(!r.has_changed());
+        assert_eq!(r.get(), None);
+        assert_eq!(r.try_changed(), None);
+    }
+
+    #[test]
+    fn overwrite() {
+        let watch = Watch::new();
+        let s = watch.sender();
+        let mut r = watch.receiver();
+
+        s.send(1);
+        s.send(2);
+
+        assert!(r.has_changed());
+        assert_eq!(r.try_changed(), Some(2));
+        assert!(!r.has_changed());
+        assert_eq!(r.try_changed(), None);
+        assert_eq!(r.get(), Some(2));
+    }
+
+    #[test]
+    fn initial_value() {
+        let watch = Watch::new_with(7);
+        let mut r1 = watch.receiver();
+
+        assert_eq!(r1.try_changed(), Some(7));
+
+        // A new receiver sees the current value as a change
+        let mut r2 = watch.receiver();
+        assert_eq!(r2.try_changed(), Some(7));
+    }
+
+    #[test]
+    fn receivers_are_independent() {
+        let watch = Watch::new();
+        let s = watch.sender();
+        let mut r1 = watch.receiver();
+        let mut r2 = watch.receiver();
+
+        s.send(1);
+        assert_eq!(r1.try_changed(), Some(1));
+
+        s.send(2);
+        assert_eq!(r1.try_changed(), Some(2));
+        assert_eq!(r2.try_changed(), Some(2));
+    }
+
+    #[tokio::test]
+    async fn stress_watch() {
+        const NUM_RECEIVERS: usize = 100;
+        const NUM_RUNS: u32 = 1_000;
+
+        static WATCH: Watch<u32> = Watch::new();
+        let mut v = std::vec::Vec::new();
+
+        for _ in 0..NUM_RECEIVERS {
+            let mut r = WATCH.receiver();
+
+            v.push(tokio::spawn(async move {
+                let mut last = 0;
+                while last != NUM_RUNS {
+                    let val = r.changed().await;
+                    assert!(val > last);
+                    last = val;
+                }
+            }));
+        }
+
+        let s = WATCH.sender();
+        for i in 1..=NUM_RUNS {
+            s.send(i);
+            tokio::task::yield_now().await;
+        }
+
+        for v in v {
+            v.await.unwrap();
+        }
+    }
+}
@@ -13,6 +13,7 @@ pub mod monotonic;
 pub mod timer_queue;
 
 /// This indicates that there was a timeout.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct TimeoutError;
 
 /// Re-export for macros
@@ -0,0 +1,273 @@
+//! Shared resources that can be locked across `.await` points.
+//!
+//! A shared resource marked `#[async_lock]` can, besides the ordinary ceiling based `lock`, be
+//! locked by async tasks with `async_lock().await`. The returned [`AsyncLockGuard`] gives
+//! exclusive access to the resource until it is dropped, also across `.await` points. Tasks
+//! waiting for the resource are queued in FIFO order, like in `rtic_sync::arbiter::Arbiter`.
+//!
+//! The ordinary `lock` can not wait for the guard to be dropped: it runs at the ceiling priority
+//! of the resource, so the task holding the guard could never run to drop it. `lock` therefore
+//! panics if the resource is held by an [`AsyncLockGuard`], and `try_lock` returns `None` instead.
+
+use core::future::poll_fn;
+use core::ops::{Deref, DerefMut};
+use core::pin::Pin;
+use core::sync::atomic::{fence, Ordering};
+use core::task::{Poll, Waker};
+
+use crate::export::atomic::AtomicBool;
+use rtic_common::dropper::OnDrop;
+use rtic_common::wait_queue::{Link, WaitQueue};
+
+/// This is needed to make the async closure in `lock` accept that we "share"
+/// the link possible between threads.
+#[derive(Clone)]
+struct LinkPtr(*mut Option<Link<Waker>>);
+
+impl LinkPtr {
+    /// This will dereference the pointer stored within and give out an `&mut`.
+    unsafe fn get(&mut self) -> &mut Option<Link<Waker>> {
+        &mut *self.0
+    }
+}
+
+unsafe impl Send for LinkPtr {}
+unsafe impl Sync for LinkPtr {}
+
+/// The lock state of an `#[async_lock]` shared resource.
+#[doc(hidden)]
+pub struct AsyncLock {
+    wait_queue: WaitQueue,
+    taken: AtomicBool,
+}
+
+unsafe impl Sync for AsyncLock {}
+
+impl Default for AsyncLock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AsyncLock {
+    /// Create a new, unlocked, lock.
+    pub const fn new() -> Self {
+        Self {
+            wait_queue: WaitQueue::new(),
+            taken: AtomicBool::new(false),
+        }
+    }
+
+    /// Locks the resource at `ptr`, waits until the lock is granted.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to the initialized resource protected by this lock, and all accesses to
+    /// the resource must go through this lock or check `is_taken`.
+    pub async unsafe fn lock<'a, T: 'a>(&'a self, ptr: *mut T) -> AsyncLockGuard<'a, T> {
+        let mut link_ptr: Option<Link<Waker>> = None;
+
+        // Make this future `Drop`-safe.
+        // SAFETY(link_ptr): Shadow the original definition of `link_ptr` so we can't abuse it.
+        let mut link_ptr = LinkPtr(&mut link_ptr as *mut Option<Link<Waker>>);
+
+        let mut link_ptr2 = link_ptr.clone();
+        let dropper = OnDrop::new(|| {
+            // SAFETY: We only run this closure and dereference the pointer if we have
+            // exited the `poll_fn` below in the `drop(dropper)` call. The other dereference
+            // of this pointer is in the `poll_fn`.
+            critical_section::with(|_| {
+                if let Some(link) = unsafe { link_ptr2.get() } {
+                    if link.is_popped() {
+                        // The lock has been handed over, but the future was dropped before
+                        // taking it. Pass it on, as `AsyncLockGuard` would on drop.
+                        self.release();
+                    } else {
+                        link.remove_from_list(&self.wait_queue);
+                    }
+                }
+            })
+        });
+
+        poll_fn(|cx| {
+            critical_section::with(|_| {
+                fence(Ordering::SeqCst);
+
+                // The queue is empty and noone holds the lock.
+                if self.wait_queue.is_empty() && !self.taken.load(Ordering::Relaxed) {
+                    self.taken.store(true, Ordering::Relaxed);
+
+                    return Poll::Ready(());
+                }
+
+                // SAFETY: This pointer is only dereferenced here and on drop of the future
+                // which happens outside this `poll_fn`'s stack frame.
+                let link = unsafe { link_ptr.get() };
+                if let Some(link) = link {
+                    // The lock has been handed over to us.
+                    if link.is_popped() {
+                        return Poll::Ready(());
+                    }
+                } else {
+                    // Place the link in the wait queue on first run.
+                    let link_ref = link.insert(Link::new(cx.waker().clone()));
+
+                    // SAFETY(new_unchecked): The address to the link is stable as it is defined
+                    // outside this stack frame.
+                    // SAFETY(push): `link_ref` lifetime comes from `link_ptr` that is shadowed,
+                    // and  we make sure in `dropper` that the link is removed from the queue
+                    // before dropping `link_ptr` AND `dropper` makes sure that the shadowed
+                    // `link_ptr` lives until the end of the stack frame.
+                    unsafe { self.wait_queue.push(Pin::new_unchecked(link_ref)) };
+                }
+
+                Poll::Pending
+            })
+        })
+        .await;
+
+        // The lock has been taken, the link is not in the queue.
+        dropper.defuse();
+
+        // SAFETY: One only gets here if the lock is held.
+        AsyncLockGuard {
+            lock: self,
+            value: unsafe { &mut *ptr },
+        }
+    }
+
+    /// Returns true if the resource is held by an [`AsyncLockGuard`].
+    ///
+    /// Called by `try_lock` of the resource, at the ceiling priority of the resource.
+    #[inline(always)]
+    pub fn is_taken(&self) -> bool {
+        self.taken.load(Ordering::Relaxed)
+    }
+
+    /// Releases the lock, handing it over to the next one in queue if any.
+    fn release(&self) {
+        critical_section::with(|_| {
+            fence(Ordering::SeqCst);
+
+            if self.wait_queue.is_empty() {
+                // If noone is in queue and we release the lock, reset `taken`.
+                self.taken.store(false, Ordering::Relaxed);
+            } else if let Some(next) = self.wait_queue.pop() {
+                // Hand the lock over to the next one in queue.
+                next.wake();
+            }
+        })
+    }
+}
+
+/// Exclusive access to an `#[async_lock]` shared resource, the lock is released on drop.
+pub struct AsyncLockGuard<'a, T> {
+    lock: &'a AsyncLock,
+    value: &'a mut T,
+}
+
+impl<T> Drop for AsyncLockGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.release();
+    }
+}
+
+impl<T> Deref for AsyncLockGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
+
+impl<T> DerefMut for AsyncLockGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::Context;
+    use futures::task::noop_waker_ref;
+
+    #[test]
+    fn fifo() {
+        let lock = AsyncLock::new();
+        let mut value = 0u32;
+        let ptr = &mut value as *mut u32;
+        let mut cx = Context::from_waker(noop_waker_ref());
+
+        let mut first = pin!(unsafe { lock.lock(ptr) });
+        let Poll::Ready(guard) = first.as_mut().poll(&mut cx) else {
+            panic!("the lock is free");
+        };
+        assert!(lock.is_taken());
+
+        let mut second = pin!(unsafe { lock.lock(ptr) });
+        assert!(second.as_mut().poll(&mut cx).is_pending());
+
+        drop(guard);
+        assert!(lock.is_taken());
+        let Poll::Ready(guard) = second.as_mut().poll(&mut cx) else {
+            panic!("the lock has been handed over");
+        };
+
+        drop(guard);
+        assert!(!lock.is_taken());
+    }
+
+    #[test]
+    fn dropped_waiter_leaves_queue() {
+        let lock = AsyncLock::new();
+        let mut value = 0u32;
+        let ptr = &mut value as *mut u32;
+        let mut cx = Context::from_waker(noop_waker_ref());
+
+        let guard = unsafe { lock.lock(ptr) };
+        let Poll::Ready(guard) = pin!(guard).poll(&mut cx) else {
+            panic!("the lock is free");
+        };
+
+        {
+            let mut waiter = pin!(unsafe { lock.lock(ptr) });
+            assert!(waiter.as_mut().poll(&mut cx).is_pending());
+        }
+
+        drop(guard);
+        assert!(!lock.is_taken());
+    }
+
+    #[test]
+    fn dropped_handed_over_lock_is_passed_on() {
+        let lock = AsyncLock::new();
+        let mut value = 0u32;
+        let ptr = &mut value as *mut u32;
+        let mut cx = Context::from_waker(noop_waker_ref());
+
+        let guard = unsafe { lock.lock(ptr) };
+        let Poll::Ready(guard) = pin!(guard).poll(&mut cx) else {
+            panic!("the lock is free");
+        };
+
+        let mut third = pin!(unsafe { lock.lock(ptr) });
+        {
+            let mut second = pin!(unsafe { lock.lock(ptr) });
+            assert!(second.as_mut().poll(&mut cx).is_pending());
+            assert!(third.as_mut().poll(&mut cx).is_pending());
+
+            // The lock is handed over to `second`, which is dropped before taking it
+            drop(guard);
+        }
+
+        let Poll::Ready(guard) = third.as_mut().poll(&mut cx) else {
+            panic!("the lock has been passed on");
+        };
+
+        drop(guard);
+        assert!(!lock.is_taken());
+    }
+}
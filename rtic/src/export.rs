@@ -4,6 +4,10 @@ pub use atomic_polyfill as atomic;
 
 pub mod executor;
 
+pub use rtic_time::Monotonic;
+
+pub use rtic_macros::__rtic_component_callback as component_callback;
+
 // Cortex-M target (any)
 #[cfg(feature = "cortex-m")]
 pub use cortex_common::*;
@@ -11,6 +15,15 @@ pub use cortex_common::*;
 #[cfg(feature = "cortex-m")]
 mod cortex_common;
 
+// Cortex-M target with a PMSAv7 MPU
+#[cfg(any(feature = "thumbv6-backend", feature = "thumbv7-backend"))]
+pub mod mpu {
+    pub use super::cortex_mpu::*;
+}
+
+#[cfg(any(feature = "thumbv6-backend", feature = "thumbv7-backend"))]
+mod cortex_mpu;
+
 // Cortex-M target with basepri support
 #[cfg(feature = "cortex-m-basepri")]
 mod cortex_basepri;
@@ -41,8 +54,50 @@ mod slic;
 #[cfg(feature = "riscv-slic")]
 pub use slic::*;
 
+/// The priority of the running task, 0 in `init` and `idle`
+static CURRENT_PRIORITY: atomic::AtomicU8 = atomic::AtomicU8::new(0);
+
+/// Runs `f` with `priority` as the current priority, used by the `run` of the backends
+#[inline(always)]
+pub fn track_priority<F>(priority: u8, f: F)
+where
+    F: FnOnce(),
+{
+    // Handlers that preempt `f` restore the priority before returning, so this needs no
+    // read-modify-write
+    let previous = CURRENT_PRIORITY.load(atomic::Ordering::Relaxed);
+    CURRENT_PRIORITY.store(priority, atomic::Ordering::Relaxed);
+    f();
+    CURRENT_PRIORITY.store(previous, atomic::Ordering::Relaxed);
+}
+
+#[inline(always)]
+pub fn current_priority() -> u8 {
+    CURRENT_PRIORITY.load(atomic::Ordering::Relaxed)
+}
+
 #[inline(always)]
 pub fn assert_send<T: Send>() {}
 
 #[inline(always)]
 pub fn assert_sync<T: Sync>() {}
+
+#[inline(always)]
+pub fn assert_copy<T: Copy>() {}
+
+#[inline(always)]
+pub fn assert_export_c<T: crate::ExportC>() {}
+
+/// Implemented for the types of shared resources that can be read through a sequence lock
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not `Copy`, so this task can not access the shared resource with `&`",
+    label = "a writer of the resource can preempt this task, or this task is async",
+    note = "a resource read with `&` by such a task is copied out, make the type `Copy` or \
+            access the resource with a lock"
+)]
+pub trait SeqLockRead {}
+
+impl<T: Copy> SeqLockRead for T {}
+
+#[inline(always)]
+pub fn assert_seqlock_read<T: SeqLockRead>() {}
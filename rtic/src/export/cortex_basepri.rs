@@ -19,11 +19,11 @@ where
 {
     if priority == 1 {
         // If the priority of this interrupt is `1` then BASEPRI can only be `0`
-        f();
+        super::track_priority(priority, f);
         unsafe { basepri::write(0) }
     } else {
         let initial = basepri::read();
-        f();
+        super::track_priority(priority, f);
         unsafe { basepri::write(initial) }
     }
 }
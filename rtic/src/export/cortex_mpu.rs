@@ -0,0 +1,199 @@
+//! MPU stack guard and priority level isolation for ARMv6-M and ARMv7-M (PMSAv7).
+//!
+//! Region 0 is the stack guard, regions 1 to 7 each cover the storage of the isolated resources
+//! that are accessed by the same set of priority levels. The storage of a region is a single
+//! static, aligned to its size rounded up to a power of two (see [`Align`]), so the region covers
+//! exactly that static. A region denies all access while none of its levels runs; it is disabled,
+//! giving access through the default memory map, while one of them runs. This only denies access
+//! to the isolated resources of the other levels, all other memory stays accessible to every
+//! level.
+
+use cortex_m::peripheral::MPU;
+
+const MPU_CTRL_ENABLE: u32 = 1 << 0;
+const MPU_CTRL_PRIVDEFENA: u32 = 1 << 2;
+
+const RASR_ENABLE: u32 = 1 << 0;
+/// Normal memory: shareable, cacheable, no write-back
+const RASR_ATTRIBUTES: u32 = (1 << 18) | (1 << 17);
+/// Execute never, no access (AP = 0b000)
+const RASR_XN: u32 = 1 << 28;
+
+/// The mask of the MPU regions that are accessible to the running priority level.
+static mut ACCESSIBLE: u8 = 0;
+
+/// The smallest MPU region.
+#[cfg(feature = "thumbv6-backend")]
+const MIN_REGION_SIZE: usize = 256;
+#[cfg(not(feature = "thumbv6-backend"))]
+const MIN_REGION_SIZE: usize = 32;
+
+/// The size of the MPU region covering `size` bytes of storage.
+#[doc(hidden)]
+pub const fn region_size(size: usize) -> usize {
+    if size <= MIN_REGION_SIZE {
+        MIN_REGION_SIZE
+    } else {
+        size.next_power_of_two()
+    }
+}
+
+/// Aligns the storage of an MPU region to `N` bytes, through a zero-length array of
+/// `<Align<N> as Alignment>::Type`.
+#[doc(hidden)]
+pub struct Align<const N: usize>;
+
+/// Gives the type aligned to `N` bytes for [`Align<N>`].
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "the isolated resources of a set of priority levels are too large for an MPU region",
+    note = "isolated resources can take at most 16 MiB per set of priority levels"
+)]
+pub trait Alignment {
+    /// A zero-sized type aligned to `N` bytes.
+    type Type;
+}
+
+macro_rules! align {
+    ($($name:ident = $n:literal,)*) => {
+        $(
+            #[doc(hidden)]
+            #[repr(align($n))]
+            pub struct $name;
+
+            impl Alignment for Align<$n> {
+                type Type = $name;
+            }
+        )*
+    };
+}
+
+align! {
+    Align32 = 32,
+    Align64 = 64,
+    Align128 = 128,
+    Align256 = 256,
+    Align512 = 512,
+    Align1K = 1024,
+    Align2K = 2048,
+    Align4K = 4096,
+    Align8K = 8192,
+    Align16K = 16384,
+    Align32K = 32768,
+    Align64K = 65536,
+    Align128K = 131072,
+    Align256K = 262144,
+    Align512K = 524288,
+    Align1M = 1048576,
+    Align2M = 2097152,
+    Align4M = 4194304,
+    Align8M = 8388608,
+    Align16M = 16777216,
+}
+
+/// Configures `region` to deny all access to `base..base + size`.
+///
+/// # Safety
+///
+/// Must be called with interrupts disabled.
+unsafe fn deny(region: u8, base: usize, size: usize) {
+    let mpu = &*MPU::PTR;
+
+    // SIZE encodes a region of 2^(SIZE + 1) bytes
+    let size_bits = size.trailing_zeros() - 1;
+
+    mpu.rnr.write(u32::from(region));
+    mpu.rbar.write(base as u32);
+    mpu.rasr
+        .write(RASR_XN | RASR_ATTRIBUTES | (size_bits << 1) | RASR_ENABLE);
+}
+
+/// Enables the MPU, with the default memory map as background region.
+///
+/// # Safety
+///
+/// Must be called with interrupts disabled.
+#[doc(hidden)]
+pub unsafe fn enable() {
+    let mpu = &*MPU::PTR;
+
+    mpu.ctrl.write(MPU_CTRL_ENABLE | MPU_CTRL_PRIVDEFENA);
+
+    // Report violations as MemManage faults instead of HardFaults
+    #[cfg(feature = "thumbv7-backend")]
+    (*cortex_m::peripheral::SCB::PTR)
+        .shcsr
+        .modify(|shcsr| shcsr | (1 << 16));
+
+    cortex_m::asm::dsb();
+    cortex_m::asm::isb();
+}
+
+/// Installs a stack guard of `size` bytes, a power of two, at the bottom of the stack.
+///
+/// # Safety
+///
+/// Must be called once before `init`, with interrupts disabled, with the bounds of the stack
+/// from the linker symbols.
+#[doc(hidden)]
+pub unsafe fn stack_guard(bottom: u32, top: u32, size: u32) {
+    // Only the layout where the stack is placed above `.bss` is supported
+    if top <= bottom {
+        return;
+    }
+
+    // Regions are aligned to their size
+    let base = (bottom + size - 1) & !(size - 1);
+
+    if cortex_m::register::msp::read() <= base + size {
+        panic!("Stack overflow after allocating executors");
+    }
+
+    deny(0, base as usize, size as usize);
+    enable();
+}
+
+/// Denies access to `base..base + size`, the storage of the isolated resources using `region`,
+/// while none of their priority levels runs.
+///
+/// # Safety
+///
+/// Must be called after `init`, with interrupts disabled, with storage aligned to `size`, which
+/// is given by [`region_size`].
+#[doc(hidden)]
+pub unsafe fn isolate(region: u8, base: usize, size: usize) {
+    deny(region, base, size);
+}
+
+/// Switches the accessible storage to the MPU regions in `mask` (bit `N` for region `N`), i.e. to
+/// those of the priority level that runs, returns the mask of the previous level.
+#[doc(hidden)]
+#[inline(always)]
+pub fn switch(mask: u8) -> u8 {
+    cortex_m::interrupt::free(|_| unsafe {
+        let mpu = &*MPU::PTR;
+        let previous = ACCESSIBLE;
+        let changed = previous ^ mask;
+
+        if changed != 0 {
+            for region in 1..8u8 {
+                if changed & (1 << region) != 0 {
+                    mpu.rnr.write(u32::from(region));
+
+                    if mask & (1 << region) != 0 {
+                        mpu.rasr.modify(|rasr| rasr & !RASR_ENABLE);
+                    } else {
+                        mpu.rasr.modify(|rasr| rasr | RASR_ENABLE);
+                    }
+                }
+            }
+
+            ACCESSIBLE = mask;
+
+            cortex_m::asm::dsb();
+            cortex_m::asm::isb();
+        }
+
+        previous
+    })
+}
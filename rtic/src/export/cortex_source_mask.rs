@@ -67,11 +67,11 @@ impl<const M: usize> Mask<M> {
 }
 
 #[inline(always)]
-pub fn run<F>(_priority: u8, f: F)
+pub fn run<F>(priority: u8, f: F)
 where
     F: FnOnce(),
 {
-    f();
+    super::track_priority(priority, f);
 }
 
 /// Lock implementation using interrupt masking
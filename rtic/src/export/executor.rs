@@ -1,5 +1,6 @@
 use super::atomic::{AtomicBool, AtomicPtr, Ordering};
 use core::{
+    alloc::Layout,
     cell::UnsafeCell,
     future::Future,
     mem::{self, ManuallyDrop, MaybeUninit},
@@ -46,6 +47,25 @@ impl AsyncTaskExecutorPtr {
         self.ptr.store(executor as *const _ as _, Ordering::Relaxed);
     }
 
+    /// Moves `executor` into `storage`, for executors placed in a link section.
+    ///
+    /// # Safety
+    ///
+    /// Must be called once, before the executor is used, with a storage of the layout given by
+    /// `AsyncTaskExecutor::layout_n_args`.
+    #[inline(always)]
+    pub unsafe fn set_in_storage<F: Future, const N: usize>(
+        &self,
+        storage: &'static AsyncTaskExecutorStorage<N>,
+        executor: AsyncTaskExecutor<F>,
+    ) {
+        debug_assert!(mem::size_of::<AsyncTaskExecutor<F>>() <= N);
+
+        let ptr = storage.0.get() as *mut AsyncTaskExecutor<F>;
+        ptr.write(executor);
+        self.ptr.store(ptr as _, Ordering::Relaxed);
+    }
+
     #[inline(always)]
     pub fn get(&self) -> *const () {
         self.ptr.load(Ordering::Relaxed)
@@ -58,6 +78,27 @@ impl Default for AsyncTaskExecutorPtr {
     }
 }
 
+/// The maximum alignment of an executor in an `AsyncTaskExecutorStorage`.
+pub const STORAGE_ALIGN: usize = 8;
+
+/// Static storage of `N` bytes for an executor, placed in a link section.
+#[repr(C, align(8))]
+pub struct AsyncTaskExecutorStorage<const N: usize>(UnsafeCell<MaybeUninit<[u8; N]>>);
+
+unsafe impl<const N: usize> Sync for AsyncTaskExecutorStorage<N> {}
+
+impl<const N: usize> AsyncTaskExecutorStorage<N> {
+    pub const fn new() -> Self {
+        Self(UnsafeCell::new(MaybeUninit::uninit()))
+    }
+}
+
+impl<const N: usize> Default for AsyncTaskExecutorStorage<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Executor for an async task.
 pub struct AsyncTaskExecutor<F: Future> {
     // `task` is protected by the `running` flag.
@@ -77,6 +118,15 @@ macro_rules! new_n_args {
     };
 }
 
+macro_rules! layout_n_args {
+    ($name:ident, $($t:ident),*) => {
+        #[inline(always)]
+        pub const fn $name<$($t,)* Fun: Fn($($t,)*) -> F>(_f: &Fun) -> Layout {
+            Layout::new::<Self>()
+        }
+    };
+}
+
 macro_rules! from_ptr_n_args {
     ($name:ident, $($t:ident),*) => {
         #[inline(always)]
@@ -152,6 +202,30 @@ impl<F: Future> AsyncTaskExecutor<F> {
     #[rustfmt::skip]
     from_ptr_n_args!(from_ptr_16_args, A1, A2, A3, A4, A5, A6, A7, A8, A9, A10, A11, A12, A13, A14, A15, A16);
 
+    layout_n_args!(layout_0_args,);
+    layout_n_args!(layout_1_args, A1);
+    layout_n_args!(layout_2_args, A1, A2);
+    layout_n_args!(layout_3_args, A1, A2, A3);
+    layout_n_args!(layout_4_args, A1, A2, A3, A4);
+    layout_n_args!(layout_5_args, A1, A2, A3, A4, A5);
+    layout_n_args!(layout_6_args, A1, A2, A3, A4, A5, A6);
+    layout_n_args!(layout_7_args, A1, A2, A3, A4, A5, A6, A7);
+    layout_n_args!(layout_8_args, A1, A2, A3, A4, A5, A6, A7, A8);
+    layout_n_args!(layout_9_args, A1, A2, A3, A4, A5, A6, A7, A8, A9);
+    layout_n_args!(layout_10_args, A1, A2, A3, A4, A5, A6, A7, A8, A9, A10);
+    #[rustfmt::skip]
+    layout_n_args!(layout_11_args, A1, A2, A3, A4, A5, A6, A7, A8, A9, A10, A11);
+    #[rustfmt::skip]
+    layout_n_args!(layout_12_args, A1, A2, A3, A4, A5, A6, A7, A8, A9, A10, A11, A12);
+    #[rustfmt::skip]
+    layout_n_args!(layout_13_args, A1, A2, A3, A4, A5, A6, A7, A8, A9, A10, A11, A12, A13);
+    #[rustfmt::skip]
+    layout_n_args!(layout_14_args, A1, A2, A3, A4, A5, A6, A7, A8, A9, A10, A11, A12, A13, A14);
+    #[rustfmt::skip]
+    layout_n_args!(layout_15_args, A1, A2, A3, A4, A5, A6, A7, A8, A9, A10, A11, A12, A13, A14, A15);
+    #[rustfmt::skip]
+    layout_n_args!(layout_16_args, A1, A2, A3, A4, A5, A6, A7, A8, A9, A10, A11, A12, A13, A14, A15, A16);
+
     /// Check if there is an active task in the executor.
     #[inline(always)]
     pub fn is_running(&self) -> bool {
@@ -192,20 +266,23 @@ impl<F: Future> AsyncTaskExecutor<F> {
         self.set_pending();
     }
 
-    /// Poll the future in the executor.
+    /// Poll the future in the executor, returns the output of the future if it completed.
     #[inline(always)]
-    pub fn poll(&self, wake: fn()) {
+    pub fn poll(&self, wake: fn()) -> Option<F::Output> {
         if self.is_running() && self.check_and_clear_pending() {
             let waker = unsafe { Waker::from_raw(RawWaker::new(wake as *const (), &WAKER_VTABLE)) };
             let mut cx = Context::from_waker(&waker);
             let future = unsafe { Pin::new_unchecked(&mut *(self.task.get() as *mut F)) };
 
             match future.poll(&mut cx) {
-                Poll::Ready(_) => {
+                Poll::Ready(output) => {
                     self.running.store(false, Ordering::Release);
+                    return Some(output);
                 }
                 Poll::Pending => {}
             }
         }
+
+        None
     }
 }
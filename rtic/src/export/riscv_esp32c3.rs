@@ -13,7 +13,7 @@ where
 {
     if priority == 1 {
         //if priority is 1, priority thresh should be 1
-        f();
+        super::track_priority(priority, f);
         unsafe {
             (*INTERRUPT_CORE0::ptr())
                 .cpu_int_thresh()
@@ -28,7 +28,7 @@ where
                 .cpu_int_thresh()
                 .bits()
         };
-        f();
+        super::track_priority(priority, f);
         //write back old thresh
         unsafe {
             (*INTERRUPT_CORE0::ptr())
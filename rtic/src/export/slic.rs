@@ -1,4 +1,15 @@
-pub use riscv_slic::{lock, pend, run, InterruptNumber};
+pub use riscv_slic::{lock, pend, InterruptNumber};
+
+#[inline(always)]
+pub unsafe fn run<F>(priority: u8, f: F)
+where
+    F: FnOnce(),
+{
+    #[allow(unused_unsafe)]
+    unsafe {
+        riscv_slic::run(priority, || super::track_priority(priority, f));
+    }
+}
 
 #[cfg(all(feature = "riscv-slic", not(feature = "riscv-clint-backend")))]
 compile_error!("Building for the riscv-slic, but 'riscv-clint-backend' not selected");
@@ -6,6 +17,9 @@ compile_error!("Building for the riscv-slic, but 'riscv-clint-backend' not selec
 /// USE CASE RE-EXPORTS: needed for SLIC-only
 pub use riscv_slic::{self, clear_interrupts, codegen, set_interrupts, set_priority};
 
+/// Enables the core local interrupts bound to hardware tasks
+pub use riscv::register::mie;
+
 pub mod interrupt {
     pub fn disable() {
         riscv_slic::disable();
@@ -41,11 +41,53 @@ pub mod mutex {
     pub use rtic_core::Mutex;
 }
 
+pub mod async_lock;
 #[doc(hidden)]
 pub mod export;
+pub mod registry;
+pub mod seqlock;
+#[cfg(feature = "riscv")]
+pub mod stack;
+pub mod supervisor;
+pub mod watchdog;
 
 pub use export::pend;
 
+/// Returns the priority of the running task, 0 in `init` and `idle`
+///
+/// The priority is tracked by the interrupt handlers of the app, while a resource is locked this
+/// is still the priority of the task, not the ceiling of the resource. In a task bound to a
+/// RISC-V core exception or interrupt this is one above the highest priority of the app, also
+/// when the exception is a synchronous trap of a lower priority task.
+#[inline(always)]
+pub fn current_priority() -> u8 {
+    export::current_priority()
+}
+
+/// Argument types of the entry points generated by `#[task(export_c)]`
+///
+/// Implemented for the integers and floats. It can be implemented for `#[repr(C)]` types made of
+/// these, to pass them from C by value.
+///
+/// # Safety
+///
+/// The type must be FFI-safe and every bit pattern must be a valid value of the type, as C can
+/// pass anything.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can not be an argument of an `export_c` task",
+    label = "C can pass values that are not valid for this type, or the type is not FFI-safe",
+    note = "use integers or floats, or a `#[repr(C)]` type of these that implements `rtic::ExportC`"
+)]
+pub unsafe trait ExportC: Copy {}
+
+macro_rules! export_c {
+    ($($ty:ty),*) => {
+        $(unsafe impl ExportC for $ty {})*
+    };
+}
+
+export_c!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64);
+
 use core::cell::UnsafeCell;
 
 /// Internal replacement for `static mut T`
@@ -0,0 +1,141 @@
+//! Runtime registry of the software tasks of an application.
+//!
+//! With `#[app(registry = true, ..)]` the application module gets a `registry` module with a
+//! table of its software tasks, the status of each task, and `spawn_by_id` to spawn tasks that
+//! are marked `#[task(spawn_by_id)]` from their encoded arguments. This is intended for debug
+//! consoles and RPC, e.g. to list and trigger tasks over a UART during bring-up.
+
+/// Information about a software task.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TaskInfo {
+    /// The ID of the task, its index in the task table.
+    pub id: usize,
+    /// The name of the task.
+    pub name: &'static str,
+    /// The priority of the task.
+    pub priority: u8,
+    /// If the task can be spawned with `spawn_by_id`.
+    pub spawn_by_id: bool,
+}
+
+/// Error returned by `spawn_by_id`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpawnByIdError {
+    /// There is no task with this ID.
+    UnknownTask,
+    /// The task is not marked `#[task(spawn_by_id)]`.
+    NotSpawnable,
+    /// The arguments could not be decoded.
+    InvalidArguments,
+    /// The task is already running.
+    Busy,
+}
+
+/// Decoding of task arguments for `spawn_by_id`.
+///
+/// The arguments of a task are decoded one after the other from the bytes given to
+/// `spawn_by_id`, all bytes must be used.
+pub trait FromBytes: Sized {
+    /// Decodes a value from the start of `bytes`, returns it together with the remaining bytes.
+    fn from_bytes(bytes: &[u8]) -> Option<(Self, &[u8])>;
+}
+
+macro_rules! from_le_bytes {
+    ($($t:ty),*) => {
+        $(
+            impl FromBytes for $t {
+                fn from_bytes(bytes: &[u8]) -> Option<(Self, &[u8])> {
+                    const N: usize = core::mem::size_of::<$t>();
+
+                    if bytes.len() < N {
+                        return None;
+                    }
+
+                    let (value, rest) = bytes.split_at(N);
+                    // UNWRAP: `value` is exactly `N` bytes long
+                    Some((<$t>::from_le_bytes(value.try_into().unwrap()), rest))
+                }
+            }
+        )*
+    };
+}
+
+// Integers and floats are encoded as little endian.
+from_le_bytes!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl FromBytes for bool {
+    fn from_bytes(bytes: &[u8]) -> Option<(Self, &[u8])> {
+        match bytes.split_first()? {
+            (0, rest) => Some((false, rest)),
+            (1, rest) => Some((true, rest)),
+            _ => None,
+        }
+    }
+}
+
+impl<T: FromBytes, const N: usize> FromBytes for [T; N] {
+    fn from_bytes(mut bytes: &[u8]) -> Option<(Self, &[u8])> {
+        let mut values = [(); N].map(|_| None);
+
+        for value in &mut values {
+            let (v, rest) = T::from_bytes(bytes)?;
+            *value = Some(v);
+            bytes = rest;
+        }
+
+        // UNWRAP: all values have been decoded
+        Some((values.map(|v| v.unwrap()), bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers_are_little_endian() {
+        assert_eq!(u8::from_bytes(&[0x12]), Some((0x12, &[][..])));
+        assert_eq!(u16::from_bytes(&[0x34, 0x12]), Some((0x1234, &[][..])));
+        assert_eq!(i32::from_bytes(&[0xff; 4]), Some((-1, &[][..])));
+        assert_eq!(f32::from_bytes(&1.5f32.to_le_bytes()), Some((1.5, &[][..])));
+    }
+
+    #[test]
+    fn short_input() {
+        assert_eq!(u8::from_bytes(&[]), None);
+        assert_eq!(u32::from_bytes(&[1, 2, 3]), None);
+        assert_eq!(bool::from_bytes(&[]), None);
+        assert_eq!(<[u16; 2]>::from_bytes(&[1, 0, 2]), None);
+    }
+
+    #[test]
+    fn bool_values() {
+        assert_eq!(bool::from_bytes(&[0]), Some((false, &[][..])));
+        assert_eq!(bool::from_bytes(&[1]), Some((true, &[][..])));
+        assert_eq!(bool::from_bytes(&[2]), None);
+        assert_eq!(bool::from_bytes(&[0xff]), None);
+    }
+
+    #[test]
+    fn arrays() {
+        assert_eq!(
+            <[u16; 3]>::from_bytes(&[1, 0, 2, 0, 3, 0]),
+            Some(([1, 2, 3], &[][..]))
+        );
+        assert_eq!(<[u8; 0]>::from_bytes(&[]), Some(([], &[][..])));
+        assert_eq!(<[bool; 2]>::from_bytes(&[1, 2]), None);
+        assert_eq!(
+            <[[u8; 2]; 2]>::from_bytes(&[1, 2, 3, 4]),
+            Some(([[1, 2], [3, 4]], &[][..]))
+        );
+    }
+
+    #[test]
+    fn trailing_bytes() {
+        // The remaining bytes are returned for the next argument
+        assert_eq!(u8::from_bytes(&[1, 2, 3]), Some((1, &[2, 3][..])));
+        assert_eq!(u16::from_bytes(&[1, 0, 2]), Some((1, &[2][..])));
+        assert_eq!(bool::from_bytes(&[1, 0]), Some((true, &[0][..])));
+        assert_eq!(<[u8; 2]>::from_bytes(&[1, 2, 3]), Some(([1, 2], &[3][..])));
+    }
+}
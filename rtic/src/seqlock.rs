@@ -0,0 +1,141 @@
+//! Optimistic reads of shared resources.
+//!
+//! A task with shared (`&`) access to a resource that is also written by a task at a higher
+//! priority, or that is async and could hold a reference across an `.await`, can not get a plain
+//! `&T`. It instead gets a proxy with a `read()` method that copies the resource out under a
+//! [`SeqLock`], without ever raising the system ceiling, so the resource must be `Copy` for such a
+//! reader. A copy that raced with a writer is never looked at, it is thrown away and read again.
+//!
+//! Every `lock` of the resource by a writer bumps the sequence number before and after the
+//! critical section. A reader copies the resource and retries if a writer has preempted it in the
+//! meantime. As the ceiling of the resource includes the priority of all its readers, a reader can
+//! never preempt a writer, so a read retries at most once per preempting write.
+
+use core::mem::MaybeUninit;
+use core::ptr;
+use core::sync::atomic::{fence, Ordering};
+
+use crate::export::atomic::AtomicUsize;
+
+/// The sequence number of a shared resource with optimistic readers.
+#[doc(hidden)]
+pub struct SeqLock {
+    seq: AtomicUsize,
+}
+
+impl Default for SeqLock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SeqLock {
+    /// Create a new sequence number.
+    pub const fn new() -> Self {
+        Self {
+            seq: AtomicUsize::new(0),
+        }
+    }
+
+    /// Gives `f` exclusive access to the resource at `ptr`, marking it as written.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called in the critical section of the resource, and `ptr` must point to the
+    /// initialized resource protected by this lock.
+    pub unsafe fn write<T, R>(&self, ptr: *mut T, f: impl FnOnce(&mut T) -> R) -> R {
+        // Writers are serialized by the critical section, no read-modify-write is needed
+        let seq = self.seq.load(Ordering::Relaxed);
+        self.seq.store(seq.wrapping_add(1), Ordering::Relaxed);
+        fence(Ordering::Release);
+
+        let r = f(&mut *ptr);
+
+        self.seq.store(seq.wrapping_add(2), Ordering::Release);
+
+        r
+    }
+
+    /// Copies the resource at `ptr` out, retrying if it is written in the meantime.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to the initialized resource protected by this lock, and all writes to the
+    /// resource must go through [`SeqLock::write`]. `T` must be `Copy`, the app asserts this for
+    /// each reader of the resource to point the error at the reader.
+    pub unsafe fn read<T>(&self, ptr: *const T) -> T {
+        loop {
+            let before = self.seq.load(Ordering::Acquire);
+            // A torn copy is not a valid `T`, it is only used once the sequence number is checked
+            let value = ptr::read_volatile(ptr as *const MaybeUninit<T>);
+            fence(Ordering::Acquire);
+
+            // An odd sequence number means that a write is in progress, a changed one that a
+            // writer has preempted the read
+            if before & 1 == 0 && self.seq.load(Ordering::Relaxed) == before {
+                return value.assume_init();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Sample {
+        a: u32,
+        b: u64,
+    }
+
+    #[test]
+    fn write_read() {
+        let lock = SeqLock::new();
+        let mut value = Sample { a: 1, b: 2 };
+        let ptr = &mut value as *mut Sample;
+
+        assert_eq!(unsafe { lock.read(ptr) }, Sample { a: 1, b: 2 });
+
+        let r = unsafe {
+            lock.write(ptr, |value| {
+                value.a = 3;
+                value.b = 4;
+                value.a + 1
+            })
+        };
+        assert_eq!(r, 4);
+        assert_eq!(unsafe { lock.read(ptr) }, Sample { a: 3, b: 4 });
+    }
+
+    #[test]
+    fn write_marks_sequence() {
+        let lock = SeqLock::new();
+        let mut value = 0u32;
+        let ptr = &mut value as *mut u32;
+
+        // The sequence number is odd during the write, and even again after it
+        unsafe {
+            lock.write(ptr, |_| {
+                assert_eq!(lock.seq.load(Ordering::Relaxed), 1);
+            })
+        };
+        assert_eq!(lock.seq.load(Ordering::Relaxed), 2);
+
+        unsafe { lock.write(ptr, |value| *value = 5) };
+        assert_eq!(lock.seq.load(Ordering::Relaxed), 4);
+        assert_eq!(unsafe { lock.read(ptr) }, 5);
+    }
+
+    #[test]
+    fn sequence_wraps() {
+        let lock = SeqLock::new();
+        lock.seq.store(usize::MAX - 1, Ordering::Relaxed);
+        let mut value = 0u8;
+        let ptr = &mut value as *mut u8;
+
+        unsafe { lock.write(ptr, |value| *value = 7) };
+        assert_eq!(lock.seq.load(Ordering::Relaxed), 0);
+        assert_eq!(unsafe { lock.read(ptr) }, 7);
+    }
+}
@@ -0,0 +1,151 @@
+//! Stack usage measurement of the RISC-V backends.
+//!
+//! With `stack_paint = true` in the backend arguments, the free stack is painted with a known
+//! pattern before `init`. At the end of each interrupt handler the painted region is scanned for
+//! the lowest overwritten word, which gives the high-water mark of the priority level of the
+//! handler, and the used part is painted again. The scan runs in a critical section and reads
+//! every free word of the stack, so it adds interrupt latency proportional to the free stack to
+//! every handler. `stack_paint` is therefore rejected in builds without debug assertions.
+//!
+//! With `stack_guard = N` a locked PMP region of `N` bytes (a power of two) is installed at the
+//! bottom of the stack, using PMP entry 0, so that a stack overflow causes an access fault
+//! instead of silently corrupting `.bss`.
+//!
+//! Both require the stack to be placed above `.bss`, i.e. they are not used with `flip-link`.
+
+use crate::export::read_sp;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// The pattern the free stack is painted with.
+const PAINT: u32 = 0xCCCC_CCCC;
+
+/// The number of priority levels with a high-water mark, higher levels are recorded in the
+/// last one.
+pub const LEVELS: usize = 16;
+
+static BOTTOM: AtomicUsize = AtomicUsize::new(0);
+static TOP: AtomicUsize = AtomicUsize::new(0);
+static MAX_USAGE: AtomicUsize = AtomicUsize::new(0);
+static HIGH_WATER_MARKS: [AtomicUsize; LEVELS] = [const { AtomicUsize::new(0) }; LEVELS];
+
+/// Returns the maximum stack usage in bytes of the handlers at `priority`, from their entry.
+///
+/// The stack used by handlers that preempted them is not included, as it is painted again when
+/// those handlers return. This can also hide stack that a handler used before it was preempted,
+/// so the value is a lower bound.
+pub fn high_water_mark(priority: u8) -> usize {
+    HIGH_WATER_MARKS[usize::from(priority).min(LEVELS - 1)].load(Ordering::Relaxed)
+}
+
+/// Returns the maximum stack usage in bytes measured so far, over all priority levels.
+pub fn max_usage() -> usize {
+    MAX_USAGE.load(Ordering::Relaxed)
+}
+
+/// Returns the size of the stack in bytes, above the guard region.
+pub fn size() -> usize {
+    TOP.load(Ordering::Relaxed) - BOTTOM.load(Ordering::Relaxed)
+}
+
+/// Returns the lowest address of the stack that has been written since it was painted.
+#[inline(always)]
+fn lowest_used(bottom: usize, top: usize) -> usize {
+    let mut addr = bottom;
+
+    // SAFETY: `bottom..top` is the stack, which is word aligned.
+    while addr < top && unsafe { core::ptr::read_volatile(addr as *const u32) } == PAINT {
+        addr += 4;
+    }
+
+    addr
+}
+
+/// Paints `from..to`, which must be below the stack pointer.
+#[inline(always)]
+unsafe fn paint(from: usize, to: usize) {
+    let mut addr = from;
+
+    while addr < to {
+        core::ptr::write_volatile(addr as *mut u32, PAINT);
+        addr += 4;
+    }
+}
+
+/// Sets up the stack guard and paints the stack, called before `init`.
+///
+/// # Safety
+///
+/// Must be called once, with interrupts disabled, with the bounds of the stack from the linker
+/// symbols.
+#[doc(hidden)]
+#[inline(always)]
+pub unsafe fn init(bottom: usize, top: usize, paint_stack: bool, guard: usize) {
+    // Only the layout where the stack is placed above `.bss` is supported
+    if top <= bottom {
+        return;
+    }
+
+    let mut bottom = (bottom + 3) & !3;
+
+    if guard > 0 {
+        // NAPOT regions are aligned to their size
+        let base = (bottom + guard - 1) & !(guard - 1);
+
+        if read_sp() as usize <= base + guard {
+            panic!("Stack overflow after allocating executors");
+        }
+
+        let pmpaddr = (base >> 2) | ((guard >> 3) - 1);
+        // L = 1, A = NAPOT, no R/W/X permissions
+        let pmpcfg: usize = 0x98;
+
+        core::arch::asm!(
+            "csrw pmpaddr0, {addr}",
+            "csrc pmpcfg0, {mask}",
+            "csrs pmpcfg0, {cfg}",
+            addr = in(reg) pmpaddr,
+            mask = in(reg) 0xffusize,
+            cfg = in(reg) pmpcfg,
+            options(nostack),
+        );
+
+        bottom = base + guard;
+    }
+
+    BOTTOM.store(bottom, Ordering::Relaxed);
+    TOP.store(top, Ordering::Relaxed);
+
+    if paint_stack {
+        paint(bottom, read_sp() as usize);
+    }
+}
+
+/// Records the stack usage of an interrupt handler at `priority` that was entered with the
+/// stack pointer at `entry_sp`, and paints the used stack again.
+#[doc(hidden)]
+#[inline(always)]
+pub fn record(priority: u8, entry_sp: u32) {
+    critical_section::with(|_| {
+        let bottom = BOTTOM.load(Ordering::Relaxed);
+        let top = TOP.load(Ordering::Relaxed);
+
+        if top == 0 {
+            return;
+        }
+
+        let lowest = lowest_used(bottom, top);
+        let level = &HIGH_WATER_MARKS[usize::from(priority).min(LEVELS - 1)];
+        let usage = (entry_sp as usize).saturating_sub(lowest);
+
+        if usage > level.load(Ordering::Relaxed) {
+            level.store(usage, Ordering::Relaxed);
+        }
+
+        if top - lowest > MAX_USAGE.load(Ordering::Relaxed) {
+            MAX_USAGE.store(top - lowest, Ordering::Relaxed);
+        }
+
+        // SAFETY: Everything below the stack pointer is unused.
+        unsafe { paint(lowest, read_sp() as usize) };
+    })
+}
@@ -0,0 +1,42 @@
+//! Supervision of software tasks.
+//!
+//! A software task with a restart policy, `#[task(restart = always | on_error)]`, is re-spawned
+//! by its dispatcher when it completes or returns an error. `max_restarts` limits the number of
+//! consecutive restarts after an error, a successful completion resets the count. Tasks with
+//! arguments get new arguments from the function given by `restart_args`.
+//!
+//! A task that returns an error and is not restarted is reported to the `#[task_failed]` hook of
+//! the application. An application with tasks that return a `Result` or have a restart policy
+//! must have the hook.
+
+use core::fmt::Debug;
+
+/// A supervised task that has failed, passed to the `#[task_failed]` hook.
+#[derive(Clone, Copy, Debug)]
+pub struct TaskFailure<'a> {
+    /// The name of the task.
+    pub task: &'static str,
+    /// How many times the task has been restarted after an error since it last completed
+    /// successfully.
+    pub restarts: u32,
+    /// The error returned by the task.
+    pub error: &'a dyn Debug,
+}
+
+/// The output of a software task, either `()` or a `Result`.
+pub trait TaskOutput {
+    /// The error of the task, if it has failed.
+    fn error(&self) -> Option<&dyn Debug>;
+}
+
+impl TaskOutput for () {
+    fn error(&self) -> Option<&dyn Debug> {
+        None
+    }
+}
+
+impl<T, E: Debug> TaskOutput for Result<T, E> {
+    fn error(&self) -> Option<&dyn Debug> {
+        self.as_ref().err().map(|e| e as &dyn Debug)
+    }
+}
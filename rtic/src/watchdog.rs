@@ -0,0 +1,95 @@
+//! Task-level watchdog supervision.
+//!
+//! With `#[app(monotonic = Mono, ..)]` a software task can be marked
+//! `#[task(watchdog = 500.millis())]`, it must then check in through `cx.check_in()` at least once
+//! per timeout. The generated `watchdog::monitor` periodically checks all tasks that have checked
+//! in, a task is unregistered when it completes. The hardware watchdog is only fed while all of
+//! them are healthy: when a task is stuck its name is reported to the `#[watchdog_expired]` hook
+//! and the device is reset by the hardware watchdog. Unlike feeding the hardware watchdog from
+//! `idle`, this also detects a hung task that does not starve `idle`.
+//!
+//! The monitor must run at a higher priority than all tasks with a watchdog, or a task stuck in a
+//! loop would starve it and the device would be reset without the name being reported. It
+//! therefore takes the `Context` of the task it runs in, which must implement [`MonitorContext`].
+
+use core::cell::Cell;
+use critical_section::Mutex;
+use rtic_time::Monotonic;
+
+/// Implemented for the `Context` of the tasks that run at a higher priority than all tasks with a
+/// watchdog, which are the tasks that can run `watchdog::monitor`.
+#[diagnostic::on_unimplemented(
+    message = "the watchdog monitor can not run in this task",
+    label = "a stuck task with a watchdog can starve this task",
+    note = "run the monitor in an async task with a higher priority than all tasks with a watchdog"
+)]
+pub trait MonitorContext {}
+
+/// The check-in state of a task with a watchdog.
+#[doc(hidden)]
+pub struct CheckIn<M: Monotonic> {
+    deadline: Mutex<Cell<Option<M::Instant>>>,
+}
+
+impl<M: Monotonic> CheckIn<M> {
+    /// Create a new check-in state, the task is not registered until its first check-in.
+    pub const fn new() -> Self {
+        Self {
+            deadline: Mutex::new(Cell::new(None)),
+        }
+    }
+
+    /// Checks in, the task must check in again within `timeout`.
+    pub fn check_in(&self, timeout: M::Duration) {
+        let deadline = M::now() + timeout;
+
+        critical_section::with(|cs| self.deadline.borrow(cs).set(Some(deadline)));
+    }
+
+    /// Unregisters the task, called when the task completes.
+    pub fn clear(&self) {
+        critical_section::with(|cs| self.deadline.borrow(cs).set(None));
+    }
+
+    /// Returns if the task has checked in within its timeout, tasks that never checked in are
+    /// healthy.
+    pub fn is_healthy(&self, now: M::Instant) -> bool {
+        critical_section::with(|cs| self.deadline.borrow(cs).get())
+            .is_none_or(|deadline| now <= deadline)
+    }
+}
+
+impl<M: Monotonic> Default for CheckIn<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Checks the tasks every `period` and calls `feed` while all tasks are healthy.
+///
+/// When a task is stuck `expired` is called with its name, after which the hardware watchdog is
+/// never fed again.
+#[doc(hidden)]
+pub async fn monitor<M: Monotonic>(
+    period: M::Duration,
+    tasks: &[(&'static str, &CheckIn<M>)],
+    mut feed: impl FnMut(),
+    expired: impl FnOnce(&'static str),
+) -> ! {
+    loop {
+        let now = M::now();
+
+        if let Some((task, _)) = tasks.iter().find(|(_, check_in)| !check_in.is_healthy(now)) {
+            expired(task);
+
+            // Wait for the hardware watchdog to reset the device
+            loop {
+                core::future::pending::<()>().await;
+            }
+        }
+
+        feed();
+
+        M::delay(period).await;
+    }
+}
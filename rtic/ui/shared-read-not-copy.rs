@@ -0,0 +1,26 @@
+#![no_main]
+
+#[rtic::app(device = lm3s6965, dispatchers = [SSI0])]
+mod app {
+    pub struct Buffer([u8; 4]);
+
+    #[shared]
+    struct Shared {
+        buffer: Buffer,
+    }
+
+    #[local]
+    struct Local {}
+
+    #[init]
+    fn init(_: init::Context) -> (Shared, Local) {
+        (Shared { buffer: Buffer([0; 4]) }, Local {})
+    }
+
+    // Written at a higher priority, so `foo` can not get a plain `&Buffer`
+    #[task(priority = 1, shared = [&buffer])]
+    async fn foo(_: foo::Context) {}
+
+    #[task(binds = GPIOA, priority = 2, shared = [buffer])]
+    fn bar(_: bar::Context) {}
+}
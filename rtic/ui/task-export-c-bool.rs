@@ -0,0 +1,19 @@
+#![no_main]
+
+#[rtic::app(device = lm3s6965, dispatchers = [SSI0])]
+mod app {
+    #[shared]
+    struct Shared {}
+
+    #[local]
+    struct Local {}
+
+    #[init]
+    fn init(_: init::Context) -> (Shared, Local) {
+        (Shared {}, Local {})
+    }
+
+    // C can pass any byte, which is UB for a `bool`
+    #[task(export_c)]
+    async fn foo(_: foo::Context, _enable: bool) {}
+}
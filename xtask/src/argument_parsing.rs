@@ -124,7 +124,7 @@ impl TestMetadata {
             },
             Package::RticSync => CargoCommand::Test {
                 package: Some(package.name()),
-                features: Some("testing".to_owned()),
+                features: Some("testing,futures-03".to_owned()),
                 test: None,
                 deny_warnings: true,
             },